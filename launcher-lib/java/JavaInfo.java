@@ -1,7 +1,10 @@
 public final class JavaInfo {
     private static final String[] CHECKED_PROPERTIES = new String[] {
             "os.arch",
-            "java.version"
+            "java.version",
+            "java.vendor",
+            "java.runtime.name",
+            "sun.arch.data.model"
     };
 
     public static void main(String[] args) {
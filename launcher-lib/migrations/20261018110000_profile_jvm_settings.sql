@@ -0,0 +1,18 @@
+ALTER TABLE profiles ADD COLUMN min_memory INTEGER;
+ALTER TABLE profiles ADD COLUMN max_memory INTEGER;
+ALTER TABLE profiles ADD COLUMN gc_preset TEXT;
+
+-- move the leading -Xmx<n>G that the launcher wrote into java_args to max_memory (in MB)
+UPDATE profiles SET
+    max_memory = CAST(substr(java_args, 5, instr(java_args, 'G') - 5) AS INTEGER) * 1024,
+    java_args = ltrim(substr(java_args, instr(java_args, 'G') + 1))
+WHERE java_args LIKE '-Xmx%G%'
+    AND instr(java_args, 'G') > 5
+    AND substr(java_args, 5, instr(java_args, 'G') - 5) NOT GLOB '*[^0-9]*';
+
+UPDATE profiles SET
+    gc_preset = 'g1',
+    java_args = trim(replace(java_args, '-XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M', ''))
+WHERE instr(java_args, '-XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M') > 0;
+
+UPDATE profiles SET java_args = NULL WHERE java_args = '';
@@ -29,14 +29,33 @@ pub enum Error {
     Generic(String),
     #[error(transparent)]
     ZipError(#[from] async_zip::error::ZipError),
-    #[error("Failed sha1 check")]
-    Sha1Error,
+    #[error("Failed {0} check")]
+    HashMismatch(&'static str),
     #[error(transparent)]
     TimeError(#[from] std::time::SystemTimeError),
     #[error("Failed to convert pathbuf to str")]
     PathBufError,
     #[error(transparent)]
     VarError(#[from] std::env::VarError),
+    #[error("Game files are missing or corrupt: {}", crate::utils::describe_list(.0))]
+    BrokenFiles(Vec<crate::launcher::verify::BrokenFile>),
+    #[error("{0} is not available while offline")]
+    Offline(String),
+    #[error("Failed to download {0}")]
+    Download(Box<crate::installer::utils::DownloadError>),
+    #[error("Failed to download {} files: {}", .0.len(), crate::utils::describe_list(.0))]
+    Downloads(Vec<crate::installer::utils::DownloadError>),
+}
+
+impl Error {
+    /// Files that could not be downloaded, empty for other errors
+    pub fn failed_downloads(&self) -> &[crate::installer::utils::DownloadError] {
+        match self {
+            Error::Download(failure) => std::slice::from_ref(failure.as_ref()),
+            Error::Downloads(failed) => failed,
+            _ => &[],
+        }
+    }
 }
 
 impl Serialize for Error {
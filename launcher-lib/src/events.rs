@@ -19,6 +19,16 @@ pub enum DownloadEvent {
         amount: Option<usize>,
         message: Option<String>,
     },
+    /// Bytes and files downloaded by the current install, sent a few times a second
+    Transfer {
+        bytes_done: u64,
+        bytes_total: u64,
+        files_done: usize,
+        files_total: usize,
+        bytes_per_second: u64,
+        /// Not set until the download rate is known
+        eta_seconds: Option<u64>,
+    },
     InvalidateQuery {
         query_key: Vec<String>,
     },
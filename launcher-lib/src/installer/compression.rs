@@ -115,6 +115,30 @@ pub async fn extract_dir(
     Ok(())
 }
 
+/// Extract a gzip compressed tar archive. Unix file permissions are kept.
+pub async fn extract_tar_gz(file: &Path, outdir: &Path) -> Result<()> {
+    let file = file.to_path_buf();
+    let outdir = outdir.to_path_buf();
+
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let reader = std::io::BufReader::new(std::fs::File::open(&file)?);
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+        archive.set_preserve_permissions(true);
+        archive.set_overwrite(true);
+
+        debug!(
+            "Extracting {} to {}",
+            file.to_string_lossy(),
+            outdir.to_string_lossy()
+        );
+        archive.unpack(&outdir)?;
+
+        Ok(())
+    })
+    .await
+    .map_err(|err| Error::Generic(err.to_string()))?
+}
+
 /// Extract all files from archive
 pub async fn extract_all(archive: &mut Archive, outdir: &Path, override_files: bool) -> Result<()> {
     for index in 0..archive.file().entries().len() {
@@ -138,6 +162,8 @@ async fn extract_file_at(
         .ok_or(Error::Generic("Failed to get entry".to_string()))?;
 
     let filename = entry.filename().as_str()?;
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    let permissions = entry.unix_permissions();
 
     let file_path = if let Some(modpath) = modpath {
         outdir
@@ -162,10 +188,10 @@ async fn extract_file_at(
             tokio::fs::remove_file(&file_path).await?;
         }
 
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(&parent).await?;
-            }
+        if let Some(parent) = file_path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(&parent).await?;
         }
 
         let mut entry_reader = archive.reader_without_entry(index).await?;
@@ -178,6 +204,16 @@ async fn extract_file_at(
         let bytes = futures::io::copy(&mut entry_reader, &mut writer.compat_write()).await?;
         debug!("Extracted {} bytes from archive", bytes);
 
+        // keep the executable bit on archives that where created on unix
+        #[cfg(unix)]
+        if let Some(mode) = permissions
+            .map(|mode| u32::from(mode) & 0o777)
+            .filter(|mode| *mode != 0)
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&file_path, std::fs::Permissions::from_mode(mode)).await?;
+        }
+
         Ok(bytes)
     }
 }
@@ -233,4 +269,44 @@ mod tests {
             .await
             .expect("Failed to extract");
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_extract_tar_gz() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = temp_dir().join("tar_gz_test");
+        let archive_file = temp.join("jre.tar.gz");
+        std::fs::create_dir_all(&temp).expect("Failed to create test dir");
+
+        {
+            let file = std::fs::File::create(&archive_file).expect("Failed to create archive");
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+
+            let content = b"#!/bin/sh";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "jre/bin/java", &content[..])
+                .expect("Failed to append file");
+            builder
+                .into_inner()
+                .and_then(|encoder| encoder.finish())
+                .expect("Failed to write archive");
+        }
+
+        let outdir = temp.join("out");
+        extract_tar_gz(&archive_file, &outdir)
+            .await
+            .expect("Failed to extract");
+
+        let mode = std::fs::metadata(outdir.join("jre/bin/java"))
+            .expect("Failed to read extracted file")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
 }
@@ -5,22 +5,24 @@
 //! download urls for mods as curseforge does not provide a api for resloving mod project id's
 //! the stibility of this method is unstable as the third party api may not work in the future`
 
-use super::{InstallContent, ModpackVersion, insert_bluk_profile_content};
+use super::{InstallContent, ModpackVersion, insert_bluk_profile_content, store::ContentStore};
 use crate::{
     database::RwDatabase,
     error::{Error, Result},
     events::DownloadEvent,
     installer::{
-        compression,
-        utils::{self, REQUEST_CLIENT, get_file_hash},
+        compression, http,
+        progress::DownloadProgress,
+        utils::{self, get_file_hash},
     },
+    launcher::jvm::suggested_max_memory,
     models::{profile::Loader, setting::Setting},
-    utils::current_timestamp,
+    utils::{current_timestamp, get_ram_gb},
 };
 use futures::StreamExt;
 use normalize_path::NormalizePath;
 use serde::Deserialize;
-use std::{os::windows::fs::MetadataExt, path::PathBuf, str::FromStr, time::Duration};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 use tauri::Url;
 use urlencoding::encode;
 use uuid::Uuid;
@@ -79,6 +81,18 @@ pub async fn install_curseforge_modpack(
     db: &RwDatabase,
     config: InstallContent,
     on_event: &tauri::ipc::Channel<DownloadEvent>,
+) -> Result<()> {
+    let _store = ContentStore::lock_install().await;
+    let progress = DownloadProgress::new(on_event.clone());
+    let result = progress.scope(install_modpack(db, config, on_event)).await;
+    progress.finish();
+    result
+}
+
+async fn install_modpack(
+    db: &RwDatabase,
+    config: InstallContent,
+    on_event: &tauri::ipc::Channel<DownloadEvent>,
 ) -> Result<()> {
     on_event
         .send(crate::events::DownloadEvent::Started {
@@ -92,6 +106,7 @@ pub async fn install_curseforge_modpack(
         .ok_or_else(|| Error::NotFound("Application path not found.".to_string()))?;
 
     let profile_direcotry = root.join("profiles").join(&config.profile);
+    let store = ContentStore::new(&root.join("runtime"));
     if !profile_direcotry.exists() {
         tokio::fs::create_dir_all(&profile_direcotry).await?;
     }
@@ -145,10 +160,11 @@ pub async fn install_curseforge_modpack(
 
     let downloads = futures::stream::iter(pack.files.into_iter().map(|file| {
         let dir = profile_direcotry.clone();
+        let store = store.clone();
         async move {
             let project_url = format!("https://api.cfwidget.com/{}", file.project_id);
 
-            let response = REQUEST_CLIENT
+            let response = http::client()
                 .request(reqwest::Method::GET, project_url)
                 .send()
                 .await?;
@@ -197,10 +213,11 @@ pub async fn install_curseforge_modpack(
             // https://mediafilez.forgecdn.net/files/5083/619/lambdynamiclights-2.3.4%2B1.20.4.jar
             let download_url = format!("https://mediafilez.forgecdn.net/files/{}/{}/{}",url_a,url_b, encode(&version.name));
 
+            DownloadProgress::expect(version.filesize, 1);
             utils::download_file(&download_url, &output, None, None).await?;
 
             let file_meta = tokio::fs::metadata(&output).await?;
-            let on_disk = file_meta.file_size();
+            let on_disk = file_meta.len();
             if on_disk != version.filesize {
                 return Err(Error::Generic(format!(
                     "On disk file size does not match expected file size. {} bytes | {} bytes for {}",
@@ -209,6 +226,7 @@ pub async fn install_curseforge_modpack(
             }
 
             let hash = get_file_hash(&output).await?;
+            store.adopt(&output, &hash).await?;
 
             on_event
         .send(crate::events::DownloadEvent::Progress {
@@ -225,35 +243,37 @@ pub async fn install_curseforge_modpack(
     .collect::<Vec<Result<(String, String, String)>>>()
     .await;
 
-    {
+    let mods = {
         let wdb = db.write().await;
         let mut data = Vec::new();
-        for file in downloads {
+        for content in utils::collect_downloads(downloads)? {
             let profile_id = config.profile.clone();
-            match file {
-                Ok(content) => {
-                    let content_type = match content.2.as_str() {
-                        "mods" => "Mod",
-                        "resourcepacks" => "Resourcepack",
-                        "shaderpacks" => "Shader",
-                        _ => "Unknown",
-                    }
-                    .to_string();
-
-                    data.push((
-                        String::new(),
-                        content.1,
-                        profile_id,
-                        content.0,
-                        content_type,
-                    ));
-                }
-                Err(err) => return Err(err),
+            let content_type = match content.2.as_str() {
+                "mods" => "Mod",
+                "resourcepacks" => "Resourcepack",
+                "shaderpacks" => "Shader",
+                _ => "Unknown",
             }
+            .to_string();
+
+            data.push((
+                String::new(),
+                content.1,
+                profile_id,
+                content.0,
+                content_type,
+            ));
         }
 
+        let mods = data
+            .iter()
+            .filter(|(_, _, _, _, content_type)| content_type == "Mod")
+            .count();
+
         insert_bluk_profile_content(data, &wdb).await?;
-    }
+
+        mods
+    };
 
     compression::extract_dir(
         &mut archive,
@@ -346,14 +366,15 @@ pub async fn install_curseforge_modpack(
         None,
     ))?;
 
+    let max_memory = suggested_max_memory(get_ram_gb(), mods as u64) as i64;
     sqlx::query!(
-        "INSERT INTO profiles ('id','name','date_created','version','loader','loader_version','java_args','state','is_modpack') VALUES (?,?,current_timestamp,?,?,?,?,?,?);",
+        "INSERT INTO profiles ('id','name','date_created','version','loader','loader_version','max_memory','gc_preset','state','is_modpack') VALUES (?,?,current_timestamp,?,?,?,?,'g1',?,?);",
         profile_id,
         pack_name,
         pack.minecraft.version,
         loader,
         loader_version,
-        "-Xmx4G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M",
+        max_memory,
         "INSTALLED",
         pack_data
     )
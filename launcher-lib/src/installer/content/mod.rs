@@ -1,13 +1,17 @@
 pub mod curseforge;
 pub mod file;
 mod mrpack;
+pub mod store;
 use std::{path::PathBuf, str::FromStr};
 
 use crate::{
     database::{Database, RwDatabase},
     error::{Error, Result},
     events::DownloadEvent,
-    installer::utils,
+    installer::{
+        progress::DownloadProgress,
+        utils::{self, FileHash},
+    },
     models::setting::Setting,
 };
 
@@ -15,6 +19,7 @@ use futures::StreamExt;
 pub use mrpack::install_mrpack;
 use serde::{Deserialize, Serialize};
 use sqlx::QueryBuilder;
+use store::ContentStore;
 use tokio::fs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,10 +61,28 @@ impl ContentType {
 #[derive(Debug, Deserialize, Clone)]
 pub struct InstallFile {
     pub sha1: String,
+    #[serde(default)]
+    pub sha512: Option<String>,
     pub url: String,
     pub version: String,
     pub filename: String,
     pub id: String,
+    /// Size in bytes, not known for files that are imported from disk
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+impl InstallFile {
+    /// Strongest hash of the file
+    pub fn strongest(&self) -> FileHash<'_> {
+        let sha1 = FileHash::Sha1(&self.sha1);
+        FileHash::strongest(
+            [Some(sha1), self.sha512.as_deref().map(FileHash::Sha512)]
+                .into_iter()
+                .flatten(),
+        )
+        .unwrap_or(sha1)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -81,29 +104,30 @@ pub struct UpdateContent {
     content_type: UpdateContentType,
 }
 
-async fn download_files(output_direcotry: &std::path::Path, files: Vec<InstallFile>) -> Result<()> {
+async fn download_files(
+    store: &ContentStore,
+    output_direcotry: &std::path::Path,
+    files: Vec<InstallFile>,
+) -> Result<()> {
+    DownloadProgress::expect(files.iter().filter_map(|file| file.size).sum(), files.len());
+
     let result = futures::stream::iter(files.into_iter().map(|file| async move {
         let name = file
             .url
             .split('/')
-            .last()
+            .next_back()
             .ok_or_else(|| Error::NotFound("Failed to get file name".to_string()))?;
         let file_dir = output_direcotry.join(name);
-        utils::download_file(&file.url, &file_dir, None, Some(&file.sha1)).await?;
+        store
+            .install(&file.url, &file.sha1, file.strongest(), &file_dir)
+            .await?;
 
         Ok(())
     }))
     .buffer_unordered(50)
     .collect::<Vec<Result<()>>>()
     .await;
-    if result.iter().any(|e| e.is_err()) {
-        result.iter().for_each(|e| {
-            if let Err(error) = e {
-                log::error!("{}", error);
-            }
-        });
-        return Err(Error::Generic("Failed to download libraries".to_string()));
-    }
+    utils::collect_downloads(result)?;
 
     Ok(())
 }
@@ -144,12 +168,28 @@ pub async fn install_content(
     icon: Option<String>,
     db: &RwDatabase,
     on_event: &tauri::ipc::Channel<DownloadEvent>,
+) -> Result<()> {
+    let _store = ContentStore::lock_install().await;
+    let progress = DownloadProgress::new(on_event.clone());
+    let result = progress
+        .scope(install_content_files(config, icon, db, on_event))
+        .await;
+    progress.finish();
+    result
+}
+
+async fn install_content_files(
+    config: InstallContent,
+    icon: Option<String>,
+    db: &RwDatabase,
+    on_event: &tauri::ipc::Channel<DownloadEvent>,
 ) -> Result<()> {
     let root = Setting::path("path.app", db)
         .await?
         .ok_or_else(|| Error::NotFound("failed to get application path".to_string()))?;
 
     let profile_direcotry = root.join("profiles").join(&config.profile);
+    let store = ContentStore::new(&root.join("runtime"));
 
     match config.content_type {
         ContentType::Resourcepack => {
@@ -173,7 +213,7 @@ pub async fn install_content(
                 })
                 .map_err(|err| Error::Generic(err.to_string()))?;
 
-            download_files(&resource_packs, config.files.clone()).await?;
+            download_files(&store, &resource_packs, config.files.clone()).await?;
 
             on_event
                 .send(crate::events::DownloadEvent::Progress {
@@ -218,7 +258,7 @@ pub async fn install_content(
                 })
                 .map_err(|err| Error::Generic(err.to_string()))?;
 
-            download_files(&shader_directory, config.files.clone()).await?;
+            download_files(&store, &shader_directory, config.files.clone()).await?;
 
             on_event
                 .send(crate::events::DownloadEvent::Progress {
@@ -263,7 +303,7 @@ pub async fn install_content(
                 })
                 .map_err(|err| Error::Generic(err.to_string()))?;
 
-            download_files(&mod_direcotry, config.files.clone()).await?;
+            download_files(&store, &mod_direcotry, config.files.clone()).await?;
 
             on_event
                 .send(crate::events::DownloadEvent::Progress {
@@ -304,7 +344,8 @@ pub async fn install_content(
             let file_path = if file.url.starts_with("https://") {
                 let id = uuid::Uuid::new_v4();
                 let temp = std::env::temp_dir().join(format!("{id}.mrpack"));
-                utils::download_file(&file.url, &temp, None, Some(&file.sha1)).await?;
+                DownloadProgress::expect(file.size.unwrap_or_default(), 1);
+                utils::download_file(&file.url, &temp, None, Some(file.strongest())).await?;
                 temp
             } else {
                 from_path = true;
@@ -367,12 +408,28 @@ pub async fn install_update(
     config: UpdateContent,
     db: &RwDatabase,
     on_event: &tauri::ipc::Channel<DownloadEvent>,
+) -> Result<String> {
+    let _store = ContentStore::lock_install().await;
+    let progress = DownloadProgress::new(on_event.clone());
+    let result = progress
+        .scope(install_update_files(profile_id, config, db, on_event))
+        .await;
+    progress.finish();
+    result
+}
+
+async fn install_update_files(
+    profile_id: &str,
+    config: UpdateContent,
+    db: &RwDatabase,
+    on_event: &tauri::ipc::Channel<DownloadEvent>,
 ) -> Result<String> {
     let root = Setting::path("path.app", db)
         .await?
         .ok_or_else(|| Error::NotFound("failed to get application path".to_string()))?;
 
     let profile_direcotry = root.join("profiles").join(profile_id);
+    let store = ContentStore::new(&root.join("runtime"));
 
     match config.content_type {
         UpdateContentType::Modpack => {
@@ -389,7 +446,8 @@ pub async fn install_update(
 
             let id = uuid::Uuid::new_v4();
             let temp = std::env::temp_dir().join(format!("{id}.mrpack"));
-            utils::download_file(&file.url, &temp, None, Some(&file.sha1)).await?;
+            DownloadProgress::expect(file.size.unwrap_or_default(), 1);
+            utils::download_file(&file.url, &temp, None, Some(file.strongest())).await?;
 
             on_event
                 .send(crate::events::DownloadEvent::Progress {
@@ -404,7 +462,7 @@ pub async fn install_update(
             }
 
             let pack_info =
-                mrpack::unpack_mrpack(on_event, &temp, &profile_direcotry, true).await?;
+                mrpack::unpack_mrpack(on_event, &store, &temp, &profile_direcotry, true).await?;
 
             if temp.is_file() && temp.exists() {
                 tokio::fs::remove_file(&temp).await?;
@@ -418,6 +476,8 @@ pub async fn install_update(
 
                 pack_info.insert_files_to_db(&wdb, profile_id).await?;
             }
+            // files of the previous version
+            store.prune(db).await?;
 
             on_event
                 .send(crate::events::DownloadEvent::Progress {
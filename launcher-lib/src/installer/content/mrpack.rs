@@ -14,16 +14,18 @@ use crate::{
     events::DownloadEvent,
     installer::{
         compression,
-        utils::{self},
+        progress::DownloadProgress,
+        utils::{self, FileHash},
     },
+    launcher::jvm::suggested_max_memory,
     models::profile::Loader,
-    utils::current_timestamp,
+    utils::{current_timestamp, get_ram_gb},
 };
 use futures::StreamExt;
 use serde::Deserialize;
 use tokio::fs::{self, File};
 
-use super::{ModpackVersion, insert_bluk_profile_content};
+use super::{ModpackVersion, insert_bluk_profile_content, store::ContentStore};
 
 const WHITELISTED_DOMAINS: [&str; 4] = [
     "https://cdn.modrinth.com",
@@ -35,6 +37,20 @@ const WHITELISTED_DOMAINS: [&str; 4] = [
 #[derive(Debug, Deserialize, Clone)]
 struct Hashs {
     sha1: String,
+    sha512: Option<String>,
+}
+
+impl Hashs {
+    /// Strongest hash of the file
+    fn strongest(&self) -> FileHash<'_> {
+        let sha1 = FileHash::Sha1(&self.sha1);
+        FileHash::strongest(
+            [Some(sha1), self.sha512.as_deref().map(FileHash::Sha512)]
+                .into_iter()
+                .flatten(),
+        )
+        .unwrap_or(sha1)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -49,6 +65,8 @@ struct PackFile {
     hashes: Hashs,
     env: Option<Env>,
     downloads: Vec<String>,
+    #[serde(default)]
+    file_size: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -87,6 +105,14 @@ pub struct PackData {
 }
 
 impl PackData {
+    /// Number of files that are installed into the mods directory
+    fn mod_count(&self) -> u64 {
+        self.files
+            .iter()
+            .filter(|file| file.path.starts_with("mods"))
+            .count() as u64
+    }
+
     pub async fn insert_files_to_db(&self, db: &Database, profile_id: &str) -> Result<()> {
         let mut data = Vec::new();
         for file in &self.files {
@@ -129,6 +155,7 @@ impl PackData {
 /// handle the core unpacking of the mrpack archive into the given profile directory
 pub async fn unpack_mrpack(
     event: &Emitter,
+    store: &ContentStore,
     mrpack_path: &Path,
     output_directory: &Path,
     override_existing: bool,
@@ -177,6 +204,8 @@ pub async fn unpack_mrpack(
         })
         .map_err(|err| Error::Generic(err.to_string()))?;
 
+    DownloadProgress::expect(files.iter().map(|file| file.file_size).sum(), files.len());
+
     let downloads = futures::stream::iter(files.into_iter().map(|file| async move {
         let source = file.downloads.first().ok_or(Error::NotFound(format!(
             "Failed to get download url for {}",
@@ -192,7 +221,9 @@ pub async fn unpack_mrpack(
 
         let output = output_directory.join(&file.path).normalize();
 
-        utils::download_file(source, &output, None, Some(&file.hashes.sha1)).await?;
+        store
+            .install(source, &file.hashes.sha1, file.hashes.strongest(), &output)
+            .await?;
 
         event
             .send(crate::events::DownloadEvent::Progress {
@@ -207,14 +238,7 @@ pub async fn unpack_mrpack(
     .collect::<Vec<Result<()>>>()
     .await;
 
-    if downloads.iter().any(|e| e.is_err()) {
-        downloads.iter().for_each(|e| {
-            if let Err(error) = e {
-                log::error!("{}", error);
-            }
-        });
-        return Err(Error::Generic("Failed to download file(s)".to_string()));
-    }
+    utils::collect_downloads(downloads)?;
 
     compression::extract_dir(
         &mut archive,
@@ -292,7 +316,8 @@ pub async fn install_mrpack(
         fs::create_dir_all(&current_profile_dir).await?;
     }
 
-    let pack = unpack_mrpack(on_event, mrpack_path, &current_profile_dir, false).await?;
+    let store = ContentStore::new(&runtime_directory.join("runtime"));
+    let pack = unpack_mrpack(on_event, &store, mrpack_path, &current_profile_dir, false).await?;
 
     let title = format!(
         "Minecraft {} {} {}",
@@ -340,20 +365,21 @@ pub async fn install_mrpack(
         project_id,
     ))?;
 
+    let max_memory = suggested_max_memory(get_ram_gb(), pack.mod_count()) as i64;
     sqlx::query!(
-        "INSERT INTO profiles ('id','name','icon','date_created','version','loader','loader_version','java_args','state','is_modpack') VALUES (?,?,?,current_timestamp,?,?,?,?,?,?);",
-            profile_id,
-            pack.name,
-            icon,
-            pack.minecraft,
-            loader,
-            pack.loader_version,
-            "-Xmx4G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M",
-            "INSTALLED",
-            pack_data
-        )
-        .execute(&wdb.0)
-        .await?;
+        "INSERT INTO profiles ('id','name','icon','date_created','version','loader','loader_version','max_memory','gc_preset','state','is_modpack') VALUES (?,?,?,current_timestamp,?,?,?,?,'g1',?,?);",
+        profile_id,
+        pack.name,
+        icon,
+        pack.minecraft,
+        loader,
+        pack.loader_version,
+        max_memory,
+        "INSTALLED",
+        pack_data
+    )
+    .execute(&wdb.0)
+    .await?;
 
     on_event
         .send(crate::events::DownloadEvent::Progress {
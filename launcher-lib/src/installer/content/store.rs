@@ -0,0 +1,209 @@
+//! Shared store of content files keyed by their sha1 hash.
+//!
+//! Files are kept once in `runtime/store/<first two chars>/<sha1>` and linked into the profiles.
+//! Profiles get a hardlink to the stored file, when that is not possible the file is copied,
+//! which clones the file on filesystems that support reflinks.
+//!
+//! A hardlinked file is the same file in every profile that links it, when a mod rewrites its own jar
+//! the change is seen by all of them. Stored files are verified before they are linked again,
+//! so a changed file is replaced for the next install instead of being spread to more profiles.
+//!
+//! Stored files are counted by the rows of `profile_content` with their hash,
+//! [`ContentStore::prune`] removes the ones that are no longer used by any profile.
+//! Installs hold [`ContentStore::lock_install`] until their files are recorded, so prune does not
+//! remove a file that was stored but is not in `profile_content` yet.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use log::{debug, warn};
+use tokio::fs;
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::database::RwDatabase;
+use crate::error::{Error, Result};
+use crate::installer::utils::{self, FileHash};
+
+lazy_static::lazy_static! {
+    /// Shared by running installs, exclusive while pruning
+    static ref STORE_LOCK: RwLock<()> = RwLock::new(());
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentStore {
+    directory: PathBuf,
+}
+
+impl ContentStore {
+    pub fn new(runtime_directory: &Path) -> Self {
+        Self {
+            directory: runtime_directory.join("store"),
+        }
+    }
+
+    /// Keep [`ContentStore::prune`] from running until the guard is dropped.
+    /// Installs hold it until the files they stored are recorded in `profile_content`.
+    pub async fn lock_install() -> RwLockReadGuard<'static, ()> {
+        STORE_LOCK.read().await
+    }
+
+    pub fn blob_path(&self, sha1: &str) -> PathBuf {
+        self.directory
+            .join(sha1.get(0..2).unwrap_or("00"))
+            .join(sha1)
+    }
+
+    /// Download a file into the store if it is not stored yet and link it to the output path.
+    /// The file is stored under its sha1 and verified with `hash`.
+    pub async fn install(
+        &self,
+        url: &str,
+        sha1: &str,
+        hash: FileHash<'_>,
+        output: &Path,
+    ) -> Result<()> {
+        let blob = self.blob_path(sha1);
+        utils::download_file(url, &blob, None, Some(hash)).await?;
+        link(&blob, output).await
+    }
+
+    /// Move a file that is already on disk into the store and replace it with a link.
+    /// The file is dropped when the hash is already stored and the stored file still has it.
+    pub async fn adopt(&self, path: &Path, sha1: &str) -> Result<()> {
+        let blob = self.blob_path(sha1);
+
+        if blob.exists() && !FileHash::Sha1(sha1).verify(&blob).await? {
+            warn!("Stored file {} was changed, replacing it", sha1);
+            fs::remove_file(&blob).await?;
+        }
+
+        if blob.exists() {
+            fs::remove_file(path).await?;
+        } else {
+            if let Some(parent) = blob.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            if fs::rename(path, &blob).await.is_err() {
+                fs::copy(path, &blob).await?;
+                fs::remove_file(path).await?;
+            }
+        }
+
+        link(&blob, path).await
+    }
+
+    /// Remove every stored file whose hash is not in `profile_content`.
+    /// Returns the number of removed files.
+    pub async fn prune(&self, db: &RwDatabase) -> Result<usize> {
+        if !self.directory.exists() {
+            return Ok(0);
+        }
+
+        // wait for running installs to record their files
+        let _lock = STORE_LOCK.write().await;
+
+        let used = {
+            let rdb = db.read().await;
+            sqlx::query_scalar::<_, String>("SELECT DISTINCT sha1 FROM profile_content;")
+                .fetch_all(&rdb.0)
+                .await?
+                .into_iter()
+                .collect::<HashSet<String>>()
+        };
+
+        let mut removed = 0;
+        let mut prefixes = fs::read_dir(&self.directory).await?;
+        while let Some(prefix) = prefixes.next_entry().await? {
+            if !prefix.file_type().await?.is_dir() {
+                continue;
+            }
+
+            let mut blobs = fs::read_dir(prefix.path()).await?;
+            while let Some(blob) = blobs.next_entry().await? {
+                let name = blob.file_name();
+                let name = name.to_string_lossy();
+                // unfinished downloads may belong to a install that is running
+                if name.ends_with(".part")
+                    || name.ends_with(".part.validator")
+                    || used.contains(name.as_ref())
+                {
+                    continue;
+                }
+
+                if let Err(err) = fs::remove_file(blob.path()).await {
+                    warn!("Failed to remove stored file {}: {}", name, err);
+                    continue;
+                }
+                removed += 1;
+            }
+        }
+
+        debug!("Removed {} unused files from the content store", removed);
+
+        Ok(removed)
+    }
+}
+
+/// Replace the output with a hardlink to the stored file, copying it when linking fails.
+async fn link(blob: &Path, output: &Path) -> Result<()> {
+    let parent = output
+        .parent()
+        .ok_or_else(|| Error::Generic("Output path has no parent directory".to_string()))?;
+    fs::create_dir_all(parent).await?;
+
+    if fs::symlink_metadata(output).await.is_ok() {
+        fs::remove_file(output).await?;
+    }
+
+    if let Err(err) = fs::hard_link(blob, output).await {
+        debug!("Failed to hardlink {}, copying: {}", output.display(), err);
+        // std copy clones the file on filesystems with reflink support
+        fs::copy(blob, output).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_adopt() {
+        let temp = std::env::temp_dir().join("content_store_test");
+        if temp.exists() {
+            fs::remove_dir_all(&temp)
+                .await
+                .expect("Failed to clear dir");
+        }
+        let store = ContentStore::new(&temp);
+        let sha1 = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+        let first = temp.join("profiles").join("a").join("mods").join("abc.jar");
+        let second = temp.join("profiles").join("b").join("mods").join("abc.jar");
+        for path in [&first, &second] {
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(path, b"abc").await.unwrap();
+            store.adopt(path, sha1).await.expect("Failed to adopt file");
+        }
+
+        let blob = store.blob_path(sha1);
+        assert!(blob.ends_with("store/a9/a9993e364706816aba3e25717850c26c9cd0d89d"));
+        assert_eq!(fs::read(&blob).await.unwrap(), b"abc");
+        assert_eq!(fs::read(&first).await.unwrap(), b"abc");
+        assert_eq!(fs::read(&second).await.unwrap(), b"abc");
+
+        // a profile keeps its file when it is removed from the store
+        fs::remove_file(&blob).await.unwrap();
+        assert_eq!(fs::read(&second).await.unwrap(), b"abc");
+
+        // a stored file that was changed is replaced
+        fs::write(&blob, b"changed").await.unwrap();
+        store
+            .adopt(&second, sha1)
+            .await
+            .expect("Failed to adopt file");
+        assert_eq!(fs::read(&blob).await.unwrap(), b"abc");
+
+        fs::remove_dir_all(&temp).await.unwrap();
+    }
+}
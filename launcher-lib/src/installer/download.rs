@@ -1,109 +1,17 @@
-use super::{compression, utils};
+use super::{
+    compression,
+    endpoints::Endpoint,
+    progress::DownloadProgress,
+    utils::{self, FileHash},
+};
 use crate::error::{Error, Result};
 use crate::events::DownloadEvent;
-use crate::java::check_java;
-use crate::manifest::{self, Downloads, Library, asset_index::AssetIndex};
+use crate::manifest::{self, Downloads, Library, Logging, asset_index::AssetIndex};
 use futures::StreamExt;
 use log::warn;
 
 use normalize_path::NormalizePath;
-use serde::Deserialize;
-use std::path::PathBuf;
-use tokio::{fs, io::AsyncWriteExt};
-
-const JAVA_DOWNLOAD_URL: &str = "https://api.azul.com/metadata/v1/zulu/packages";
-
-#[derive(Debug, Deserialize)]
-struct JavaDownload {
-    download_url: String,
-    name: String,
-    java_version: Vec<usize>,
-}
-
-/// Downloads the zulu jre
-pub async fn download_java(
-    runtime_directory: &std::path::Path,
-    java: usize,
-) -> Result<(String, PathBuf)> {
-    let java_directory = runtime_directory.join("java").normalize();
-
-    let temp = std::env::temp_dir();
-
-    let url = &format!(
-        "{}?arch={}&java_version={}&os={}&archive_type=zip&javafx_bundled=false&java_package_type=jre&page_size=1",
-        JAVA_DOWNLOAD_URL,
-        std::env::consts::ARCH,
-        java,
-        std::env::consts::OS
-    );
-
-    let request = utils::REQUEST_CLIENT.get(url);
-
-    let response = request.send().await?;
-
-    let result = response.json::<Vec<JavaDownload>>().await?;
-    let java_download = result.first().ok_or(Error::NotFound(
-        "The required java version was not found".to_string(),
-    ))?;
-
-    let java_vesrion = java_download
-        .java_version
-        .iter()
-        .map(|e| e.to_string())
-        .collect::<Vec<String>>()
-        .join(".");
-
-    let temp_file = temp.join(&java_download.name);
-
-    let mut jrep = utils::REQUEST_CLIENT
-        .get(&java_download.download_url)
-        .send()
-        .await?;
-
-    if temp_file.exists() {
-        fs::remove_file(&temp_file).await?;
-    }
-
-    let mut file = tokio::fs::OpenOptions::new()
-        .create(true)
-        .truncate(false)
-        .read(true)
-        .write(true)
-        .create_new(true)
-        .open(&temp_file)
-        .await?;
-
-    while let Some(mut chunk) = jrep.chunk().await? {
-        file.write_all_buf(&mut chunk).await?;
-    }
-
-    file.sync_data().await?;
-
-    let mut archive = compression::open_archive(file).await?;
-
-    compression::extract_all(&mut archive, &java_directory, false).await?;
-
-    fs::remove_file(temp_file).await?;
-
-    let java = java_directory
-        .join(java_download.name.replace(".zip", ""))
-        .join("bin")
-        .join("javaw.exe")
-        .normalize();
-
-    let test_java = check_java(&java)
-        .await?
-        .ok_or_else(|| Error::Generic("Java install check failed.".to_string()))?;
-
-    if !test_java.starts_with(&java_vesrion) {
-        return Err(Error::Generic(format!(
-            "Java version returned was not what was expected. Found '{}' was expecting '{}'",
-            test_java, java_vesrion
-        )));
-    }
-
-    Ok((java_vesrion, java))
-}
+use tokio::fs;
 
 /// Downloads the minecraft client jar file
 ///
@@ -124,11 +32,13 @@ pub async fn download_client(
 
     log::debug!("Client jar file path: {}", client_jar.to_string_lossy());
 
+    DownloadProgress::expect(downloads.client.size as u64, 1);
+
     utils::download_file(
         &downloads.client.url,
         &client_jar,
         None,
-        Some(&downloads.client.sha1),
+        Some(FileHash::Sha1(&downloads.client.sha1)),
     )
     .await?;
 
@@ -155,6 +65,27 @@ pub async fn download_libraries(
     let library_directory = runtime_directory.join("libraries");
     let natvies_directory = runtime_directory.join("natives").join(version);
 
+    // libraries without a download section have no size, natives are added when they are downloaded
+    let (bytes, files) = libraries
+        .iter()
+        .filter(|lib| {
+            lib.rules.as_ref().is_none_or(|rules| {
+                rules
+                    .iter()
+                    .all(|condition| condition.parse(None).unwrap_or(false))
+            })
+        })
+        .filter_map(|lib| match &lib.downloads {
+            Some(downloads) => downloads
+                .artifact
+                .as_ref()
+                .filter(|artifact| !artifact.url.is_empty())
+                .map(|artifact| artifact.size as u64),
+            None => Some(0),
+        })
+        .fold((0, 0), |(bytes, files), size| (bytes + size, files + 1));
+    DownloadProgress::expect(bytes, files);
+
     let installed = futures::stream::iter(libraries.into_iter().map(|lib| {
         let lib_dir = library_directory.clone();
         let nat_dir = natvies_directory.clone();
@@ -172,30 +103,38 @@ pub async fn download_libraries(
             let path = lib_dir.join(&artifact_path).normalize();
 
             match &lib.downloads {
-                Some(downloads) => {
-                    if !downloads.artifact.url.is_empty() {
+                Some(downloads) => match &downloads.artifact {
+                    Some(artifact) if !artifact.url.is_empty() => {
                         utils::download_file(
-                            &downloads.artifact.url,
+                            &artifact.url,
                             &path,
                             None,
-                            Some(&downloads.artifact.sha1),
+                            Some(FileHash::Sha1(&artifact.sha1)),
                         )
                         .await?;
-                    } else {
+                    }
+                    Some(_) => {
                         warn!(
                             "Lib {} does not have a download url! Most likely a forge library.",
                             artifact_name
                         );
                     }
-                }
+                    // legacy native only library, handled below.
+                    None => {}
+                },
                 None => {
                     let url = format!(
                         "{}{}",
-                        &lib.url
-                            .unwrap_or("https://libraries.minecraft.net/".to_string()),
+                        &lib.url.unwrap_or_else(|| Endpoint::Libraries.url("/")),
                         &artifact_path
                     );
-                    utils::download_file(&url, &path, None, lib.sha1.as_deref()).await?;
+                    utils::download_file(
+                        &url,
+                        &path,
+                        None,
+                        lib.sha1.as_deref().map(FileHash::Sha1),
+                    )
+                    .await?;
                 }
             }
 
@@ -217,13 +156,20 @@ pub async fn download_libraries(
                     let file = classifier
                         .url
                         .split('/')
-                        .last()
+                        .next_back()
                         .ok_or(Error::Generic("Failed to get file name".to_string()))?;
                     let temp = std::env::temp_dir();
                     let path = temp.join(file).normalize();
 
-                    utils::download_file(&classifier.url, &path, None, Some(&classifier.sha1))
-                        .await?;
+                    DownloadProgress::expect(classifier.size as u64, 1);
+
+                    utils::download_file(
+                        &classifier.url,
+                        &path,
+                        None,
+                        Some(FileHash::Sha1(&classifier.sha1)),
+                    )
+                    .await?;
 
                     let mut archive =
                         compression::open_archive(fs::File::open(&path).await?).await?;
@@ -241,14 +187,7 @@ pub async fn download_libraries(
     .collect::<Vec<Result<()>>>()
     .await;
 
-    if installed.iter().any(|e| e.is_err()) {
-        installed.iter().for_each(|e| {
-            if let Err(error) = e {
-                log::error!("{}", error);
-            }
-        });
-        return Err(Error::Generic("Failed to download libraries".to_string()));
-    }
+    utils::collect_downloads(installed)?;
 
     on_event
         .send(crate::events::DownloadEvent::Progress {
@@ -270,6 +209,7 @@ pub async fn download_assets(
 ) -> Result<()> {
     let assets_index =
         assets_index.ok_or(Error::NotFound("Asset Index was not found".to_string()))?;
+    DownloadProgress::expect(assets_index.size as u64, 1);
 
     let assets_objects_directory = runtime_directory.join("assets/objects");
     let assets_index_path = runtime_directory
@@ -285,7 +225,7 @@ pub async fn download_assets(
         &assets_index.url,
         &assets_index_path,
         None,
-        Some(&assets_index.sha1),
+        Some(FileHash::Sha1(&assets_index.sha1)),
     )
     .await?;
 
@@ -298,6 +238,15 @@ pub async fn download_assets(
 
     let asset_manifest: AssetIndex = serde_json::from_reader(&mut reader)?;
 
+    DownloadProgress::expect(
+        asset_manifest
+            .objects
+            .values()
+            .map(|asset| asset.size as u64)
+            .sum(),
+        asset_manifest.objects.len(),
+    );
+
     let assets = futures::stream::iter(asset_manifest.objects.into_iter().map(|(key, asset)| {
         let root = assets_objects_directory.clone();
         async move {
@@ -314,9 +263,9 @@ pub async fn download_assets(
 
             let file_path = root.join(&hash).normalize();
 
-            let url = format!("https://resources.download.minecraft.net/{}", hash);
+            let url = Endpoint::Resources.url(&format!("/{}", hash));
 
-            utils::download_file(&url, &file_path, None, Some(&asset.hash)).await?;
+            utils::download_file(&url, &file_path, None, Some(FileHash::Sha1(&asset.hash))).await?;
             Ok(())
         }
     }))
@@ -324,14 +273,7 @@ pub async fn download_assets(
     .collect::<Vec<Result<()>>>()
     .await;
 
-    if assets.iter().any(|e| e.is_err()) {
-        assets.iter().for_each(|e| {
-            if let Err(err) = e {
-                log::error!("{}", err)
-            }
-        });
-        return Err(Error::Generic("Failed to download assets".to_string()));
-    }
+    utils::collect_downloads(assets)?;
 
     on_event
         .send(crate::events::DownloadEvent::Progress {
@@ -342,3 +284,34 @@ pub async fn download_assets(
 
     Ok(())
 }
+
+/// Downloads the client log4j configuration file defined in the manifest
+/// into `assets/log_configs`
+pub async fn download_logging_config(
+    runtime_directory: &std::path::Path,
+    logging: Option<Logging>,
+) -> Result<()> {
+    let Some(client) = logging.and_then(|logging| logging.client) else {
+        log::debug!("Manifest does not define a client logging config");
+        return Ok(());
+    };
+
+    let file_id = client.file.id.ok_or(Error::NotFound(
+        "Failed to get logging config id".to_string(),
+    ))?;
+
+    let config_path = runtime_directory
+        .join("assets/log_configs")
+        .join(file_id)
+        .normalize();
+
+    DownloadProgress::expect(client.file.size as u64, 1);
+
+    utils::download_file(
+        &client.file.url,
+        &config_path,
+        None,
+        Some(FileHash::Sha1(&client.file.sha1)),
+    )
+    .await
+}
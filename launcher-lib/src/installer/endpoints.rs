@@ -0,0 +1,262 @@
+//! Base urls of the remote services used by the installers.
+//!
+//! Every request url is passed through [`rewrite`] before it is sent, urls that start
+//! with the default base of a [`Endpoint`] are moved to the configured base.
+//! This covers the urls that come from version manifests as well as the ones built here.
+//!
+//! Settings
+//! - `option.mirror` either `bmclapi` or the base url of a mirror with the BMCLAPI layout.
+//! - `endpoint.<name>` base url of a single endpoint, takes priority over the mirror.
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::database::RwDatabase;
+use crate::error::{Error, Result};
+use crate::models::setting::Setting;
+
+/// Base url of the public BMCLAPI mirror
+pub const BMCLAPI: &str = "https://bmclapi2.bangbang93.com";
+
+lazy_static::lazy_static! {
+    static ref ENDPOINTS: RwLock<Arc<Endpoints>> = RwLock::new(Arc::new(Endpoints::default()));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    LauncherMeta,
+    PistonMeta,
+    PistonData,
+    Launcher,
+    Libraries,
+    Resources,
+    ForgeMaven,
+    NeoForgeMaven,
+    FabricMeta,
+    FabricMaven,
+    QuiltMeta,
+    QuiltMaven,
+    Azul,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 13] = [
+        Endpoint::LauncherMeta,
+        Endpoint::PistonMeta,
+        Endpoint::PistonData,
+        Endpoint::Launcher,
+        Endpoint::Libraries,
+        Endpoint::Resources,
+        Endpoint::ForgeMaven,
+        Endpoint::NeoForgeMaven,
+        Endpoint::FabricMeta,
+        Endpoint::FabricMaven,
+        Endpoint::QuiltMeta,
+        Endpoint::QuiltMaven,
+        Endpoint::Azul,
+    ];
+
+    /// Name used in the `endpoint.<name>` setting
+    pub fn name(self) -> &'static str {
+        match self {
+            Endpoint::LauncherMeta => "launcher_meta",
+            Endpoint::PistonMeta => "piston_meta",
+            Endpoint::PistonData => "piston_data",
+            Endpoint::Launcher => "launcher",
+            Endpoint::Libraries => "libraries",
+            Endpoint::Resources => "resources",
+            Endpoint::ForgeMaven => "forge_maven",
+            Endpoint::NeoForgeMaven => "neoforge_maven",
+            Endpoint::FabricMeta => "fabric_meta",
+            Endpoint::FabricMaven => "fabric_maven",
+            Endpoint::QuiltMeta => "quilt_meta",
+            Endpoint::QuiltMaven => "quilt_maven",
+            Endpoint::Azul => "azul",
+        }
+    }
+
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Endpoint::LauncherMeta => "https://launchermeta.mojang.com",
+            Endpoint::PistonMeta => "https://piston-meta.mojang.com",
+            Endpoint::PistonData => "https://piston-data.mojang.com",
+            Endpoint::Launcher => "https://launcher.mojang.com",
+            Endpoint::Libraries => "https://libraries.minecraft.net",
+            Endpoint::Resources => "https://resources.download.minecraft.net",
+            Endpoint::ForgeMaven => "https://maven.minecraftforge.net",
+            Endpoint::NeoForgeMaven => "https://maven.neoforged.net/releases",
+            Endpoint::FabricMeta => "https://meta.fabricmc.net",
+            Endpoint::FabricMaven => "https://maven.fabricmc.net",
+            Endpoint::QuiltMeta => "https://meta.quiltmc.org",
+            Endpoint::QuiltMaven => "https://maven.quiltmc.org/repository/release",
+            Endpoint::Azul => "https://api.azul.com",
+        }
+    }
+
+    /// Default url of a path on this endpoint
+    pub fn url(self, path: &str) -> String {
+        format!("{}{}", self.default_url(), path)
+    }
+
+    /// Base of this endpoint on a mirror using the BMCLAPI layout.
+    /// Azul has no mirror.
+    fn bmclapi_url(self, mirror: &str) -> Option<String> {
+        let path = match self {
+            Endpoint::LauncherMeta
+            | Endpoint::PistonMeta
+            | Endpoint::PistonData
+            | Endpoint::Launcher => "",
+            Endpoint::Libraries
+            | Endpoint::ForgeMaven
+            | Endpoint::NeoForgeMaven
+            | Endpoint::FabricMaven
+            | Endpoint::QuiltMaven => "/maven",
+            Endpoint::Resources => "/assets",
+            Endpoint::FabricMeta => "/fabric-meta",
+            Endpoint::QuiltMeta => "/quilt-meta",
+            Endpoint::Azul => return None,
+        };
+
+        Some(format!("{}{}", mirror.trim_end_matches('/'), path))
+    }
+}
+
+/// Configured base urls, endpoints that are not set use their default url.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Endpoints {
+    urls: HashMap<Endpoint, String>,
+}
+
+impl Endpoints {
+    /// Move every endpoint that the mirror provides to it
+    pub fn with_mirror(mut self, mirror: &str) -> Self {
+        for endpoint in Endpoint::ALL {
+            if let Some(url) = endpoint.bmclapi_url(mirror) {
+                self.urls.insert(endpoint, url);
+            }
+        }
+        self
+    }
+
+    pub fn with(mut self, endpoint: Endpoint, url: &str) -> Self {
+        self.urls
+            .insert(endpoint, url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub async fn from_settings(db: &RwDatabase) -> Result<Self> {
+        let mut endpoints = Self::default();
+
+        if let Some(mirror) = Setting::get("option.mirror", db).await? {
+            match mirror.value.trim() {
+                "" | "none" => {}
+                "bmclapi" => endpoints = endpoints.with_mirror(BMCLAPI),
+                url if url.starts_with("http://") || url.starts_with("https://") => {
+                    endpoints = endpoints.with_mirror(url)
+                }
+                value => {
+                    return Err(Error::Generic(format!("Invalid mirror: {}", value)));
+                }
+            }
+        }
+
+        for endpoint in Endpoint::ALL {
+            let key = format!("endpoint.{}", endpoint.name());
+            if let Some(setting) = Setting::get(&key, db).await?
+                && !setting.value.trim().is_empty()
+            {
+                endpoints = endpoints.with(endpoint, setting.value.trim());
+            }
+        }
+
+        Ok(endpoints)
+    }
+
+    pub fn base_url(&self, endpoint: Endpoint) -> &str {
+        self.urls
+            .get(&endpoint)
+            .map_or(endpoint.default_url(), String::as_str)
+    }
+
+    /// Move a url from the default base of a endpoint to the configured one.
+    /// The longest matching base wins, urls of unknown hosts are returned as is.
+    pub fn rewrite(&self, url: &str) -> String {
+        Endpoint::ALL
+            .iter()
+            .filter_map(|endpoint| {
+                let rest = url.strip_prefix(endpoint.default_url())?;
+                (rest.is_empty() || rest.starts_with(['/', '?'])).then_some((endpoint, rest))
+            })
+            .max_by_key(|(endpoint, _)| endpoint.default_url().len())
+            .map_or_else(
+                || url.to_string(),
+                |(endpoint, rest)| format!("{}{}", self.base_url(*endpoint), rest),
+            )
+    }
+
+    pub fn shared() -> Arc<Self> {
+        ENDPOINTS
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Replace the endpoints used by all requests
+    pub fn configure(endpoints: Endpoints) {
+        let mut shared = ENDPOINTS.write().unwrap_or_else(|err| err.into_inner());
+        if **shared != endpoints {
+            *shared = Arc::new(endpoints);
+        }
+    }
+
+    pub async fn configure_from_settings(db: &RwDatabase) -> Result<()> {
+        Self::configure(Self::from_settings(db).await?);
+        Ok(())
+    }
+}
+
+/// Rewrite a url with the shared endpoints
+pub fn rewrite(url: &str) -> String {
+    Endpoints::shared().rewrite(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite() {
+        let endpoints = Endpoints::default()
+            .with_mirror(BMCLAPI)
+            .with(Endpoint::Resources, "http://127.0.0.1:8080/assets/");
+
+        assert_eq!(
+            endpoints.rewrite("https://libraries.minecraft.net/org/ow2/asm/asm/9.6/asm-9.6.jar"),
+            "https://bmclapi2.bangbang93.com/maven/org/ow2/asm/asm/9.6/asm-9.6.jar"
+        );
+        assert_eq!(
+            endpoints.rewrite(
+                &Endpoint::NeoForgeMaven.url("/net/neoforged/neoforge/maven-metadata.xml")
+            ),
+            "https://bmclapi2.bangbang93.com/maven/net/neoforged/neoforge/maven-metadata.xml"
+        );
+        assert_eq!(
+            endpoints.rewrite("https://resources.download.minecraft.net/ab/abcd"),
+            "http://127.0.0.1:8080/assets/ab/abcd"
+        );
+        // not mirrored
+        assert_eq!(
+            endpoints.rewrite("https://api.azul.com/metadata/v1/zulu/packages"),
+            "https://api.azul.com/metadata/v1/zulu/packages"
+        );
+        // only whole path segments match
+        assert_eq!(
+            endpoints.rewrite("https://libraries.minecraft.net.example.com/a.jar"),
+            "https://libraries.minecraft.net.example.com/a.jar"
+        );
+        assert_eq!(
+            Endpoints::default()
+                .rewrite("https://launchermeta.mojang.com/mc/game/version_manifest_v2.json"),
+            "https://launchermeta.mojang.com/mc/game/version_manifest_v2.json"
+        );
+    }
+}
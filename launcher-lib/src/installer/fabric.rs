@@ -1,16 +1,14 @@
 use crate::{
     error::{Error, Result},
     events::DownloadEvent,
-    installer::fabric_core,
+    installer::{endpoints::Endpoint, fabric_core},
 };
 use normalize_path::NormalizePath;
 use std::path::Path;
 
-const FABRIC_LOADER_VERSION_LIST_URL: &str = "https://meta.fabricmc.net/v2/versions/loader";
-const FABRIC_INSTALLER_LIST_URL: &str =
-    "https://maven.fabricmc.net/net/fabricmc/fabric-installer/maven-metadata.xml";
-const FABRIC_INSTALLER_DOWNLOAD_URL: &str =
-    "https://maven.fabricmc.net/net/fabricmc/fabric-installer/";
+const FABRIC_LOADER_VERSION_LIST: &str = "/v2/versions/loader";
+const FABRIC_INSTALLER_LIST: &str = "/net/fabricmc/fabric-installer/maven-metadata.xml";
+const FABRIC_INSTALLER_DOWNLOAD: &str = "/net/fabricmc/fabric-installer/";
 
 /// Install the fabric or quilt mod loader.
 pub async fn run_installer(
@@ -29,13 +27,18 @@ pub async fn run_installer(
     let loader_version = if let Some(version) = loader_version {
         version
     } else {
-        fabric_core::get_latest_loader_version(FABRIC_LOADER_VERSION_LIST_URL).await?
+        fabric_core::get_latest_loader_version(
+            &Endpoint::FabricMeta.url(FABRIC_LOADER_VERSION_LIST),
+        )
+        .await?
     };
-    let installer_version = fabric_core::get_latest_installer(FABRIC_INSTALLER_LIST_URL).await?;
-    let installer_url = format!(
+    let installer_version =
+        fabric_core::get_latest_installer(&Endpoint::FabricMaven.url(FABRIC_INSTALLER_LIST))
+            .await?;
+    let installer_url = Endpoint::FabricMaven.url(&format!(
         "{0}{1}/fabric-installer-{1}.jar",
-        FABRIC_INSTALLER_DOWNLOAD_URL, installer_version
-    );
+        FABRIC_INSTALLER_DOWNLOAD, installer_version
+    ));
 
     let modded_version = format!("fabric-loader-{}-{}", loader_version, version);
     let install_args = vec![
@@ -4,6 +4,7 @@ use crate::{
     events::DownloadEvent,
     installer::download::download_libraries,
     manifest::Manifest,
+    offline,
 };
 use normalize_path::NormalizePath;
 use serde::Deserialize;
@@ -16,9 +17,7 @@ struct LoaderVersion {
 
 /// get either fabric or quilt's latest loader version
 pub async fn get_latest_loader_version(url: &str) -> Result<String> {
-    let response = utils::REQUEST_CLIENT.get(url).send().await?;
-
-    let data = response.json::<Vec<LoaderVersion>>().await?;
+    let data = serde_json::from_str::<Vec<LoaderVersion>>(&offline::fetch_cached(url).await?)?;
 
     let latest = data.first().ok_or(Error::NotFound(
         "Failed to get latest loader version".to_string(),
@@ -29,8 +28,7 @@ pub async fn get_latest_loader_version(url: &str) -> Result<String> {
 
 /// get latest installer
 pub async fn get_latest_installer(url: &str) -> Result<String> {
-    let response = utils::REQUEST_CLIENT.get(url).send().await?;
-    let xml = response.text().await?;
+    let xml = offline::fetch_cached(url).await?;
     let (_, version) = lazy_regex::regex_captures!("<latest>(?<version>.+)</latest>", &xml).ok_or(
         Error::NotFound("Failed to get fabric latest version".to_string()),
     )?;
@@ -117,7 +115,7 @@ pub async fn run_fabric_like_installer(
         .join(format!("fabric-like-installer-{}.jar", temp_name))
         .normalize();
 
-    utils::download_file(installer_url, &installer_file, None, None).await?;
+    utils::download_maven_file(installer_url, &installer_file).await?;
 
     if !installer_path.exists() {
         return Err(Error::NotFound("Failed to find installer".to_string()));
@@ -11,6 +11,7 @@ use tokio::fs::{self, File};
 
 use super::{
     compression::{self, open_archive},
+    endpoints::Endpoint,
     utils,
 };
 
@@ -18,10 +19,10 @@ use crate::{
     error::{Error, Result},
     events::DownloadEvent,
     manifest::{Library, Manifest, MavenRepository},
+    offline,
 };
 
-const FORGE_VERSION_LIST_URL: &str =
-    "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml";
+const FORGE_VERSION_LIST: &str = "/net/minecraftforge/forge/maven-metadata.xml";
 
 #[derive(Debug, Deserialize)]
 struct Mapping {
@@ -198,12 +199,7 @@ pub async fn get_latest_version(minecraft_version: &str) -> Result<String> {
         r"<version>{minecraft_version}-(?<loader_version>\d+\.\d+\.\d+)<\/version>"
     ))?;
 
-    let response = utils::REQUEST_CLIENT
-        .get(FORGE_VERSION_LIST_URL)
-        .send()
-        .await?;
-
-    let data = response.text().await?;
+    let data = offline::fetch_cached(&Endpoint::ForgeMaven.url(FORGE_VERSION_LIST)).await?;
 
     let cap = regex
         .captures(&data)
@@ -228,10 +224,10 @@ pub async fn get_installer_download_url(
     };
 
     let forge_version = format!("{}-{}", minecraft, loader_version);
-    let url = format!(
-        "https://maven.minecraftforge.net/net/minecraftforge/forge/{0}/forge-{0}-installer.jar",
+    let url = Endpoint::ForgeMaven.url(&format!(
+        "/net/minecraftforge/forge/{0}/forge-{0}-installer.jar",
         forge_version
-    );
+    ));
 
     Ok((loader_version, url))
 }
@@ -254,7 +250,7 @@ pub async fn run_installer(
             fs::remove_file(&installer_path).await?;
         }
 
-        utils::download_file(&download_url, &installer_path, None, None).await?;
+        utils::download_maven_file(&download_url, &installer_path).await?;
 
         log::debug!("Extracting and parseing install_profile");
         let mut archive = open_archive(File::open(&installer_path).await?).await?;
@@ -497,6 +493,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs network access"]
     async fn test_get_latest() {
         let version = get_latest_version("1.20.4")
             .await
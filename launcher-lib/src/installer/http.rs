@@ -0,0 +1,254 @@
+//! Shared http client used by the installers.
+//!
+//! The client is built from a [`HttpConfig`] and rebuilt by [`HttpConfig::configure`] when it changes.
+//! Requests take a clone of the current client, so running downloads keep the client they started with.
+//!
+//! Settings
+//! - `option.http_proxy` url of a proxy for all requests, credentials can be part of the url.
+//! - `option.http_proxy_username` and `option.http_proxy_password` credentials of the proxy,
+//!   they are stored unencrypted in the settings table like every other setting.
+//! - `option.http_ca_bundle` path of a pem file with root certificates to trust next to the system ones.
+//! - `option.http_connect_timeout` and `option.http_read_timeout` in seconds, not limited if not set or 0.
+//! - `option.http_prefer_ipv4` `TRUE` to try the ipv4 addresses of a host before its ipv6 ones.
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use crate::database::RwDatabase;
+use crate::error::{Error, Result};
+use crate::models::setting::Setting;
+
+lazy_static::lazy_static! {
+    static ref HTTP_CLIENT: RwLock<Arc<(HttpConfig, reqwest::Client)>> = {
+        let config = HttpConfig::default();
+        let client = config.build().expect("Request client construct failed.");
+        RwLock::new(Arc::new((config, client)))
+    };
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct HttpConfig {
+    pub proxy: Option<String>,
+    /// username and password of the proxy
+    pub proxy_auth: Option<(String, String)>,
+    pub ca_bundle: Option<PathBuf>,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub prefer_ipv4: bool,
+}
+
+// the proxy url can contain credentials, so it is left out like the proxy credentials
+impl std::fmt::Debug for HttpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpConfig")
+            .field("proxy", &self.proxy.as_ref().map(|_| "<redacted>"))
+            .field(
+                "proxy_auth",
+                &self.proxy_auth.as_ref().map(|_| "<redacted>"),
+            )
+            .field("ca_bundle", &self.ca_bundle)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("prefer_ipv4", &self.prefer_ipv4)
+            .finish()
+    }
+}
+
+/// Value of a setting, empty values count as not set
+async fn setting(key: &str, db: &RwDatabase) -> Result<Option<String>> {
+    Ok(Setting::get(key, db)
+        .await?
+        .map(|setting| setting.value.trim().to_string())
+        .filter(|value| !value.is_empty()))
+}
+
+async fn timeout_setting(key: &str, db: &RwDatabase) -> Result<Option<Duration>> {
+    let Some(value) = setting(key, db).await? else {
+        return Ok(None);
+    };
+    let seconds = value
+        .parse::<u64>()
+        .map_err(|err| Error::Generic(format!("Invalid {}: {}", key, err)))?;
+
+    Ok((seconds > 0).then(|| Duration::from_secs(seconds)))
+}
+
+impl HttpConfig {
+    pub async fn from_settings(db: &RwDatabase) -> Result<Self> {
+        let proxy = setting("option.http_proxy", db).await?;
+        let proxy_auth = match (
+            setting("option.http_proxy_username", db).await?,
+            setting("option.http_proxy_password", db).await?,
+        ) {
+            (Some(username), password) => Some((username, password.unwrap_or_default())),
+            _ => None,
+        };
+
+        Ok(Self {
+            proxy,
+            proxy_auth,
+            ca_bundle: setting("option.http_ca_bundle", db)
+                .await?
+                .map(PathBuf::from),
+            connect_timeout: timeout_setting("option.http_connect_timeout", db).await?,
+            read_timeout: timeout_setting("option.http_read_timeout", db).await?,
+            prefer_ipv4: setting("option.http_prefer_ipv4", db)
+                .await?
+                .is_some_and(|value| value == "TRUE"),
+        })
+    }
+
+    pub fn build(&self) -> Result<reqwest::Client> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let header = reqwest::header::HeaderValue::from_str(&format!(
+            "VisualSource/rusty-mc-launcher/{}",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .map_err(|err| Error::Generic(err.to_string()))?;
+
+        headers.insert(reqwest::header::USER_AGENT, header);
+
+        let mut builder = reqwest::Client::builder()
+            .tcp_keepalive(Some(Duration::from_secs(10)))
+            .default_headers(headers);
+
+        if let Some(url) = &self.proxy {
+            let mut proxy = reqwest::Proxy::all(url)?;
+            if let Some((username, password)) = &self.proxy_auth {
+                proxy = proxy.basic_auth(username, password);
+            }
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let bundle = std::fs::read(path).map_err(|err| {
+                Error::Generic(format!(
+                    "Failed to read ca bundle {}: {}",
+                    path.to_string_lossy(),
+                    err
+                ))
+            })?;
+            for certificate in reqwest::Certificate::from_pem_bundle(&bundle)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if self.prefer_ipv4 {
+            builder = builder.dns_resolver(Arc::new(PreferIpv4Resolver));
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Rebuild the shared client if the config is different from the one it was built with
+    pub fn configure(config: HttpConfig) -> Result<()> {
+        if HTTP_CLIENT.read().unwrap_or_else(|err| err.into_inner()).0 == config {
+            return Ok(());
+        }
+
+        let client = config.build()?;
+        // the config is not logged as it can contain the proxy credentials
+        log::debug!("Rebuilt http client");
+        *HTTP_CLIENT.write().unwrap_or_else(|err| err.into_inner()) = Arc::new((config, client));
+
+        Ok(())
+    }
+
+    pub async fn configure_from_settings(db: &RwDatabase) -> Result<()> {
+        Self::configure(Self::from_settings(db).await?)
+    }
+}
+
+/// System resolver that puts the ipv4 addresses first.
+/// The connector tries the first address family and falls back to the other one,
+/// so hosts that only have ipv6 addresses can still be reached.
+struct PreferIpv4Resolver;
+
+impl Resolve for PreferIpv4Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            Ok(Box::new(prefer_ipv4(addrs).into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Move the ipv4 addresses in front, keeping the order of the resolver within each family
+fn prefer_ipv4(mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    addrs.sort_by_key(SocketAddr::is_ipv6);
+    addrs
+}
+
+/// The shared client
+pub fn client() -> reqwest::Client {
+    HTTP_CLIENT
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .1
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let config = HttpConfig {
+            proxy: Some("http://proxy.example.com:3128".to_string()),
+            proxy_auth: Some(("user".to_string(), "password".to_string())),
+            connect_timeout: Some(Duration::from_secs(10)),
+            read_timeout: Some(Duration::from_secs(30)),
+            prefer_ipv4: true,
+            ..Default::default()
+        };
+        config.build().expect("Failed to build client");
+
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("password"));
+        assert!(!debug.contains("proxy.example.com"));
+
+        let invalid = HttpConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.build().is_err());
+
+        let missing = HttpConfig {
+            ca_bundle: Some(std::env::temp_dir().join("missing_ca_bundle.pem")),
+            ..Default::default()
+        };
+        assert!(missing.build().is_err());
+    }
+
+    #[test]
+    fn test_prefer_ipv4() {
+        let addrs: Vec<SocketAddr> = [
+            "[2001:db8::1]:0",
+            "192.0.2.1:0",
+            "[2001:db8::2]:0",
+            "192.0.2.2:0",
+        ]
+        .into_iter()
+        .map(|addr| addr.parse().unwrap())
+        .collect();
+
+        let sorted: Vec<String> = prefer_ipv4(addrs)
+            .iter()
+            .map(|addr| addr.ip().to_string())
+            .collect();
+        assert_eq!(
+            sorted,
+            ["192.0.2.1", "192.0.2.2", "2001:db8::1", "2001:db8::2"]
+        );
+    }
+}
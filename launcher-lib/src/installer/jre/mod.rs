@@ -0,0 +1,113 @@
+mod mojang;
+pub mod runtimes;
+mod zulu;
+
+use normalize_path::NormalizePath;
+use std::path::{Path, PathBuf};
+
+use crate::database::RwDatabase;
+use crate::error::Result;
+use crate::java::JAVA_EXECUTABLE;
+use crate::manifest::JavaVersion;
+use crate::models::setting::Setting;
+
+pub use mojang::Mojang;
+pub use zulu::Zulu;
+
+/// A source that jre's can be installed from.
+pub trait JreProvider {
+    /// Install a jre into the java directory.
+    /// Returns the version of the installed jre and the path to its java executable.
+    async fn install(&self, java_directory: &Path, java: &JavaVersion)
+    -> Result<(String, PathBuf)>;
+
+    /// Get the newest build that the provider has for the java version.
+    async fn latest_build(&self, java: &JavaVersion) -> Result<String>;
+}
+
+/// The jre provider selected with the `option.jre_provider` setting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// Azul Zulu build of the java major version
+    #[default]
+    Zulu,
+    /// The runtime component from Mojang's java-runtime manifest, as used by the official launcher
+    Mojang,
+}
+
+impl Provider {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("zulu") | None => Self::Zulu,
+            Some("mojang") => Self::Mojang,
+            Some(value) => {
+                log::warn!("Unknown jre provider '{}', using zulu.", value);
+                Self::Zulu
+            }
+        }
+    }
+
+    /// The provider that installed a runtime in `runtime/java`.
+    /// Zulu runtimes are named after the zulu package, Mojang runtimes after the runtime component.
+    pub fn of_runtime(name: &str) -> Self {
+        if name.starts_with("zulu") {
+            Self::Zulu
+        } else {
+            Self::Mojang
+        }
+    }
+
+    async fn install(self, java_directory: &Path, java: &JavaVersion) -> Result<(String, PathBuf)> {
+        match self {
+            Self::Zulu => Zulu.install(java_directory, java).await,
+            Self::Mojang => Mojang.install(java_directory, java).await,
+        }
+    }
+
+    async fn latest_build(self, java: &JavaVersion) -> Result<String> {
+        match self {
+            Self::Zulu => Zulu.latest_build(java).await,
+            Self::Mojang => Mojang.latest_build(java).await,
+        }
+    }
+}
+
+/// Install the jre for a minecraft version with the provider selected in the settings.
+pub async fn install_java(
+    runtime_directory: &Path,
+    java: &JavaVersion,
+    db: &RwDatabase,
+) -> Result<(String, PathBuf)> {
+    let java_directory = runtime_directory.join("java").normalize();
+
+    let setting = Setting::get("option.jre_provider", db).await?;
+
+    Provider::from_setting(setting.as_ref().map(|setting| setting.value.as_str()))
+        .install(&java_directory, java)
+        .await
+}
+
+/// Find the java executable of a extracted jre.
+/// Supports the `bin/java` layout and the macos `Contents/Home/bin/java` bundle layout,
+/// which is nested in a `*.jre` or `jre.bundle` directory.
+fn find_java_executable(jre_directory: &Path) -> Option<PathBuf> {
+    let mut homes = vec![
+        jre_directory.to_path_buf(),
+        jre_directory.join("Contents").join("Home"),
+    ];
+
+    if let Ok(entries) = std::fs::read_dir(jre_directory) {
+        homes.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .map(|path| path.join("Contents").join("Home")),
+        );
+    }
+
+    homes
+        .into_iter()
+        .map(|home| home.join("bin").join(JAVA_EXECUTABLE).normalize())
+        .find(|java| java.is_file())
+}
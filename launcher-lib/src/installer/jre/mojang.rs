@@ -0,0 +1,286 @@
+use futures::StreamExt;
+use normalize_path::NormalizePath;
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+use super::{JreProvider, find_java_executable};
+use crate::error::{Error, Result};
+use crate::installer::{
+    endpoints::{self, Endpoint},
+    http,
+    progress::DownloadProgress,
+    utils::{self, FileHash},
+};
+use crate::java::check_java;
+use crate::manifest::JavaVersion;
+use crate::offline;
+
+const RUNTIME_MANIFEST: &str =
+    "/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+#[derive(Debug, Deserialize)]
+struct RuntimeDownload {
+    sha1: String,
+    size: u64,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuntimeVersion {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Runtime {
+    manifest: RuntimeDownload,
+    version: RuntimeVersion,
+}
+
+/// platform -> component -> runtimes
+type RuntimeIndex = HashMap<String, HashMap<String, Vec<Runtime>>>;
+
+#[derive(Debug, Deserialize)]
+struct RuntimeFileDownloads {
+    raw: RuntimeDownload,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RuntimeFile {
+    Directory,
+    File {
+        #[serde(default)]
+        executable: bool,
+        downloads: RuntimeFileDownloads,
+    },
+    /// A symlink, the target is relative to the directory of the link
+    Link {
+        target: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct RuntimeManifest {
+    files: HashMap<String, RuntimeFile>,
+}
+
+/// Name of the current platform in the runtime manifest
+fn platform() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", "x86_64") => Some("windows-x64"),
+        ("windows", "x86") => Some("windows-x86"),
+        ("windows", "aarch64") => Some("windows-arm64"),
+        ("macos", "x86_64") => Some("mac-os"),
+        ("macos", "aarch64") => Some("mac-os-arm64"),
+        ("linux", "x86_64") => Some("linux"),
+        ("linux", "x86") => Some("linux-i386"),
+        _ => None,
+    }
+}
+
+/// Get a json document and check it against its sha1 hash
+async fn fetch_verified<T: serde::de::DeserializeOwned>(url: &str, sha1: &str) -> Result<T> {
+    let bytes = http::client()
+        .get(endpoints::rewrite(url))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    if hex::encode(Sha1::digest(&bytes)) != sha1 {
+        return Err(Error::HashMismatch("sha1"));
+    }
+
+    serde_json::from_slice(&bytes).map_err(Error::from)
+}
+
+#[cfg(unix)]
+async fn set_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn set_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Symlinks need elevated permissions on windows, so the target file is copied instead.
+#[cfg(unix)]
+async fn create_link(link: &Path, target: &str) -> Result<()> {
+    fs::symlink(target, link).await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn create_link(link: &Path, target: &str) -> Result<()> {
+    let source = link
+        .parent()
+        .map(|parent| parent.join(target).normalize())
+        .ok_or_else(|| Error::Generic("Failed to get link directory".to_string()))?;
+
+    if source.is_file() {
+        fs::copy(&source, link).await?;
+    }
+
+    Ok(())
+}
+
+/// Get the newest build of a runtime component for the current platform
+async fn find_runtime(component: &str) -> Result<Runtime> {
+    let platform = platform().ok_or_else(|| {
+        Error::NotFound(format!(
+            "Mojang does not provide java runtimes for {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+
+    let mut index = serde_json::from_str::<RuntimeIndex>(
+        &offline::fetch_cached(&Endpoint::LauncherMeta.url(RUNTIME_MANIFEST)).await?,
+    )?;
+
+    index
+        .remove(platform)
+        .and_then(|mut components| components.remove(component))
+        .and_then(|runtimes| runtimes.into_iter().next())
+        .ok_or_else(|| {
+            Error::NotFound(format!(
+                "Java runtime '{}' is not available for {}",
+                component, platform
+            ))
+        })
+}
+
+/// Installs the runtime component named by the version manifest from Mojang's java-runtime manifest.
+/// Files are downloaded one by one and checked against there sha1 hash, so a existing install is repaired.
+pub struct Mojang;
+
+impl JreProvider for Mojang {
+    async fn latest_build(&self, java: &JavaVersion) -> Result<String> {
+        Ok(find_runtime(&java.component).await?.version.name)
+    }
+
+    async fn install(
+        &self,
+        java_directory: &Path,
+        java: &JavaVersion,
+    ) -> Result<(String, PathBuf)> {
+        let runtime = find_runtime(&java.component).await?;
+
+        let manifest: RuntimeManifest =
+            fetch_verified(&runtime.manifest.url, &runtime.manifest.sha1).await?;
+
+        let jre_directory = java_directory.join(&java.component).normalize();
+
+        let mut files = Vec::new();
+        let mut links = Vec::new();
+        for (name, file) in manifest.files {
+            let path = jre_directory.join(&name).normalize();
+            if !path.starts_with(&jre_directory) {
+                return Err(Error::Generic(format!(
+                    "Runtime file '{}' is outside of the runtime directory",
+                    name
+                )));
+            }
+
+            match file {
+                RuntimeFile::Directory => fs::create_dir_all(&path).await?,
+                RuntimeFile::File {
+                    executable,
+                    downloads,
+                } => files.push((path, downloads.raw, executable)),
+                RuntimeFile::Link { target } => links.push((path, target)),
+            }
+        }
+
+        DownloadProgress::expect(
+            files.iter().map(|(_, download, _)| download.size).sum(),
+            files.len(),
+        );
+
+        let installed = futures::stream::iter(files.into_iter().map(
+            |(path, download, executable)| async move {
+                utils::download_file(
+                    &download.url,
+                    &path,
+                    None,
+                    Some(FileHash::Sha1(&download.sha1)),
+                )
+                .await?;
+                if executable {
+                    set_executable(&path).await?;
+                }
+                Ok(())
+            },
+        ))
+        .buffer_unordered(50)
+        .collect::<Vec<Result<()>>>()
+        .await;
+
+        utils::collect_downloads(installed)?;
+
+        for (link, target) in links {
+            if link.is_symlink() || link.exists() {
+                fs::remove_file(&link).await?;
+            }
+            if let Some(parent) = link.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            create_link(&link, &target).await?;
+        }
+
+        let java_exe = find_java_executable(&jre_directory).ok_or_else(|| {
+            Error::NotFound(format!(
+                "Failed to find java executable in {}",
+                jre_directory.to_string_lossy()
+            ))
+        })?;
+
+        check_java(&java_exe)
+            .await?
+            .ok_or_else(|| Error::Generic("Java install check failed.".to_string()))?;
+
+        Ok((runtime.version.name, java_exe))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_runtime_manifest() {
+        let manifest: RuntimeManifest = serde_json::from_str(
+            r#"{"files":{
+                "bin":{"type":"directory"},
+                "bin/java":{"type":"file","executable":true,"downloads":{"raw":{"sha1":"abc","size":1,"url":"https://example.com/java"}}},
+                "legal/LICENSE":{"type":"link","target":"../LICENSE"}
+            }}"#,
+        )
+        .expect("Failed to parse manifest");
+
+        assert!(matches!(
+            manifest.files.get("bin/java"),
+            Some(RuntimeFile::File {
+                executable: true,
+                ..
+            })
+        ));
+        assert!(matches!(
+            manifest.files.get("legal/LICENSE"),
+            Some(RuntimeFile::Link { target }) if target == "../LICENSE"
+        ));
+        assert!(matches!(
+            manifest.files.get("bin"),
+            Some(RuntimeFile::Directory)
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use super::Provider;
+use crate::database::RwDatabase;
+use crate::error::{Error, Result};
+use crate::installer::metadata::get_launcher_manifest;
+use crate::manifest::{JavaVersion, Manifest};
+use crate::models::setting::Setting;
+use crate::offline;
+
+/// A jre that was installed by the launcher into `runtime/java`
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledRuntime {
+    /// Name of the runtime directory
+    pub name: String,
+    pub path: PathBuf,
+    /// Major version of the `java.<major>` setting that uses the runtime.
+    /// Not set if no setting uses the runtime.
+    pub major_version: Option<usize>,
+    /// Build that was installed, from the setting metadata
+    pub build: Option<String>,
+    /// Ids of the profiles with a minecraft version that needs the runtime
+    pub profiles: Vec<String>,
+}
+
+impl InstalledRuntime {
+    fn java_version(&self) -> Result<JavaVersion> {
+        let major_version = self.major_version.ok_or_else(|| {
+            Error::Generic(format!(
+                "Runtime '{}' is not used by any java setting",
+                self.name
+            ))
+        })?;
+
+        Ok(JavaVersion {
+            component: self.name.clone(),
+            major_version,
+        })
+    }
+}
+
+async fn java_directory(db: &RwDatabase) -> Result<PathBuf> {
+    let root = Setting::path("path.app", db)
+        .await?
+        .ok_or_else(|| Error::NotFound("No runtime directory is avaiable.".into()))?;
+
+    Ok(root.join("runtime").join("java"))
+}
+
+/// Get the `java.<major>` settings, as (major, java executable, build)
+async fn java_settings(rwdb: &RwDatabase) -> Result<Vec<(usize, String, Option<String>)>> {
+    let db = rwdb.read().await;
+
+    let settings: Vec<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT key, value, metadata FROM settings WHERE key LIKE 'java.%';")
+            .fetch_all(&db.0)
+            .await?;
+
+    Ok(settings
+        .into_iter()
+        .filter_map(|(key, value, metadata)| {
+            key.strip_prefix("java.")
+                .and_then(|major| major.parse::<usize>().ok())
+                .map(|major| (major, value, metadata))
+        })
+        .collect())
+}
+
+/// The part of a version manifest that is needed to find its java version
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionJava {
+    java_version: Option<JavaVersion>,
+}
+
+/// Get the java major version a minecraft version needs.
+/// The manifest of a version that is not installed is fetched, so profiles that are not installed yet keep their runtime.
+async fn required_java(runtime_directory: &Path, version: &str) -> Result<Option<usize>> {
+    let manifest_file = runtime_directory
+        .join("versions")
+        .join(version)
+        .join(format!("{}.json", version));
+
+    let java_version = if manifest_file.is_file() {
+        Manifest::read_manifest(&manifest_file, false)
+            .await?
+            .java_version
+    } else {
+        let item = get_launcher_manifest(Some(version)).await?;
+        serde_json::from_str::<VersionJava>(&offline::fetch_cached(&item.url).await?)?.java_version
+    };
+
+    Ok(java_version.map(|java| java.major_version))
+}
+
+/// Get the ids of the profiles that need each java major version,
+/// and the ids of the profiles whose java version could not be found.
+async fn profiles_by_java(
+    runtime_directory: &Path,
+    rwdb: &RwDatabase,
+) -> Result<(HashMap<usize, Vec<String>>, Vec<String>)> {
+    let profiles: Vec<(String, String)> = {
+        let db = rwdb.read().await;
+        sqlx::query_as("SELECT id, version FROM profiles;")
+            .fetch_all(&db.0)
+            .await?
+    };
+
+    let mut versions: HashMap<String, Option<Option<usize>>> = HashMap::new();
+    let mut needed: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut unknown = Vec::new();
+    for (profile_id, version) in profiles {
+        if !versions.contains_key(&version) {
+            let major = match required_java(runtime_directory, &version).await {
+                Ok(major) => Some(major),
+                Err(err) => {
+                    log::warn!("Failed to get java version of {}: {}", version, err);
+                    None
+                }
+            };
+            versions.insert(version.clone(), major);
+        }
+
+        match versions.get(&version).copied().flatten() {
+            Some(Some(major)) => needed.entry(major).or_default().push(profile_id),
+            Some(None) => {}
+            None => unknown.push(profile_id),
+        }
+    }
+
+    Ok((needed, unknown))
+}
+
+/// List the jre's that are installed in `runtime/java` and the profiles that depend on them.
+pub async fn list_runtimes(db: &RwDatabase) -> Result<Vec<InstalledRuntime>> {
+    Ok(installed_runtimes(db).await?.0)
+}
+
+/// Get the installed runtimes and the ids of the profiles whose runtime is unknown
+async fn installed_runtimes(db: &RwDatabase) -> Result<(Vec<InstalledRuntime>, Vec<String>)> {
+    let java_directory = java_directory(db).await?;
+    if !java_directory.is_dir() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let runtime_directory = java_directory
+        .parent()
+        .ok_or_else(|| Error::Generic("Failed to get runtime directory".to_string()))?;
+
+    let settings = java_settings(db).await?;
+    let (mut profiles, unknown) = profiles_by_java(runtime_directory, db).await?;
+
+    let mut runtimes = Vec::new();
+    let mut entries = tokio::fs::read_dir(&java_directory).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let setting = settings
+            .iter()
+            .find(|(_, java_exe, _)| Path::new(java_exe).starts_with(&path));
+
+        let major_version = setting.map(|(major, _, _)| *major);
+
+        runtimes.push(InstalledRuntime {
+            name: entry.file_name().to_string_lossy().to_string(),
+            build: setting.and_then(|(_, _, build)| build.clone()),
+            profiles: major_version
+                .and_then(|major| profiles.remove(&major))
+                .unwrap_or_default(),
+            major_version,
+            path,
+        });
+    }
+
+    runtimes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok((runtimes, unknown))
+}
+
+async fn get_runtime(name: &str, db: &RwDatabase) -> Result<InstalledRuntime> {
+    list_runtimes(db)
+        .await?
+        .into_iter()
+        .find(|runtime| runtime.name == name)
+        .ok_or_else(|| Error::NotFound(format!("Java runtime '{}' is not installed", name)))
+}
+
+/// Check the provider of a runtime for a newer build of the same major version.
+/// Returns the newer build if there is one.
+pub async fn check_runtime_update(name: &str, db: &RwDatabase) -> Result<Option<String>> {
+    let runtime = get_runtime(name, db).await?;
+
+    let latest = Provider::of_runtime(&runtime.name)
+        .latest_build(&runtime.java_version()?)
+        .await?;
+
+    Ok(Some(latest).filter(|latest| runtime.build.as_ref() != Some(latest)))
+}
+
+/// Install the newest build of a runtime and point its `java.<major>` setting to it.
+/// Zulu builds are installed into a new directory, the old directory is removed after the setting is updated.
+pub async fn update_runtime(name: &str, db: &RwDatabase) -> Result<()> {
+    let runtime = get_runtime(name, db).await?;
+    let java = runtime.java_version()?;
+    let java_directory = java_directory(db).await?;
+
+    let (build, java_exe) = Provider::of_runtime(&runtime.name)
+        .install(&java_directory, &java)
+        .await?;
+
+    Setting::update(
+        &format!("java.{}", java.major_version),
+        java_exe.to_string_lossy().to_string(),
+        Some(build),
+        db,
+    )
+    .await?;
+
+    if !java_exe.starts_with(&runtime.path) {
+        tokio::fs::remove_dir_all(&runtime.path).await?;
+    }
+
+    Ok(())
+}
+
+/// Remove a runtime and its `java.<major>` setting.
+/// Fails if a profile still needs the runtime or a game is running with it,
+/// `running` are the executables of the running games.
+pub async fn uninstall_runtime(name: &str, running: &[PathBuf], db: &RwDatabase) -> Result<()> {
+    let runtime = get_runtime(name, db).await?;
+    remove_runtime(runtime, running, db).await
+}
+
+fn is_running(runtime: &InstalledRuntime, running: &[PathBuf]) -> bool {
+    running.iter().any(|exe| exe.starts_with(&runtime.path))
+}
+
+async fn remove_runtime(
+    runtime: InstalledRuntime,
+    running: &[PathBuf],
+    db: &RwDatabase,
+) -> Result<()> {
+    if !runtime.profiles.is_empty() {
+        return Err(Error::Generic(format!(
+            "Java runtime '{}' is used by {} profile(s)",
+            runtime.name,
+            runtime.profiles.len()
+        )));
+    }
+    if is_running(&runtime, running) {
+        return Err(Error::Generic(format!(
+            "Java runtime '{}' is used by a running game",
+            runtime.name
+        )));
+    }
+
+    if let Some(major) = runtime.major_version {
+        Setting::delete(&format!("java.{}", major), db).await?;
+    }
+
+    tokio::fs::remove_dir_all(&runtime.path).await?;
+
+    Ok(())
+}
+
+/// Remove every runtime that no profile needs and no running game uses,
+/// `running` are the executables of the running games.
+/// Nothing is removed if the java version of a profile can not be found.
+/// Returns the names of the removed runtimes.
+pub async fn remove_unused_runtimes(running: &[PathBuf], db: &RwDatabase) -> Result<Vec<String>> {
+    let (runtimes, unknown) = installed_runtimes(db).await?;
+    if !unknown.is_empty() {
+        return Err(Error::Generic(format!(
+            "The java version of {} profile(s) is unknown",
+            unknown.len()
+        )));
+    }
+
+    let mut removed = Vec::new();
+    for runtime in runtimes {
+        if runtime.profiles.is_empty() && !is_running(&runtime, running) {
+            removed.push(runtime.name.clone());
+            remove_runtime(runtime, running, db).await?;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_running() {
+        let runtime = InstalledRuntime {
+            name: "zulu-21".to_string(),
+            path: PathBuf::from("runtime/java/zulu-21"),
+            major_version: Some(21),
+            build: None,
+            profiles: Vec::new(),
+        };
+
+        assert!(is_running(
+            &runtime,
+            &[PathBuf::from("runtime/java/zulu-21/bin/java")]
+        ));
+        assert!(!is_running(
+            &runtime,
+            &[PathBuf::from("runtime/java/zulu-17/bin/java")]
+        ));
+        assert!(!is_running(&runtime, &[]));
+    }
+
+    #[test]
+    fn test_version_java() {
+        let version: VersionJava = serde_json::from_str(
+            r#"{"id":"1.21.1","javaVersion":{"component":"java-runtime-delta","majorVersion":21}}"#,
+        )
+        .expect("Failed to parse version");
+        assert_eq!(
+            version.java_version.map(|java| java.major_version),
+            Some(21)
+        );
+
+        let version: VersionJava =
+            serde_json::from_str(r#"{"id":"1.2.5"}"#).expect("Failed to parse version");
+        assert!(version.java_version.is_none());
+    }
+}
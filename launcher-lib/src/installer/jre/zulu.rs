@@ -0,0 +1,111 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use super::{JreProvider, find_java_executable};
+use crate::error::{Error, Result};
+use crate::installer::{compression, endpoints::Endpoint, progress::DownloadProgress, utils};
+use crate::java::check_java;
+use crate::manifest::JavaVersion;
+use crate::offline;
+
+const JAVA_PACKAGES: &str = "/metadata/v1/zulu/packages";
+
+/// Zulu only provides tar.gz archives with unix permissions for linux and macos
+const JAVA_ARCHIVE_TYPE: &str = if cfg!(windows) { "zip" } else { "tar.gz" };
+
+#[derive(Debug, Deserialize)]
+struct JavaDownload {
+    download_url: String,
+    name: String,
+    java_version: Vec<usize>,
+}
+
+impl JavaDownload {
+    fn version(&self) -> String {
+        self.java_version
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+}
+
+/// Get the newest zulu jre package of a java major version for the current platform
+async fn latest_package(major_version: usize) -> Result<JavaDownload> {
+    let url = &format!(
+        "{}?arch={}&java_version={}&os={}&archive_type={}&javafx_bundled=false&java_package_type=jre&page_size=1",
+        Endpoint::Azul.url(JAVA_PACKAGES),
+        std::env::consts::ARCH,
+        major_version,
+        std::env::consts::OS,
+        JAVA_ARCHIVE_TYPE
+    );
+
+    let result = serde_json::from_str::<Vec<JavaDownload>>(&offline::fetch_cached(url).await?)?;
+    result.into_iter().next().ok_or(Error::NotFound(
+        "The required java version was not found".to_string(),
+    ))
+}
+
+/// Installs the Azul Zulu jre for the java major version.
+pub struct Zulu;
+
+impl JreProvider for Zulu {
+    async fn latest_build(&self, java: &JavaVersion) -> Result<String> {
+        Ok(latest_package(java.major_version).await?.version())
+    }
+
+    async fn install(
+        &self,
+        java_directory: &Path,
+        java: &JavaVersion,
+    ) -> Result<(String, PathBuf)> {
+        let temp = std::env::temp_dir();
+
+        let java_download = latest_package(java.major_version).await?;
+        let java_vesrion = java_download.version();
+
+        let temp_file = temp.join(&java_download.name);
+
+        DownloadProgress::expect(0, 1);
+        utils::download_file(&java_download.download_url, &temp_file, None, None).await?;
+
+        if java_download.name.ends_with(".tar.gz") {
+            compression::extract_tar_gz(&temp_file, java_directory).await?;
+        } else {
+            let mut archive = compression::open_archive(fs::File::open(&temp_file).await?).await?;
+            compression::extract_all(&mut archive, java_directory, false).await?;
+        }
+
+        fs::remove_file(temp_file).await?;
+
+        let jre_directory = java_directory.join(
+            java_download
+                .name
+                .trim_end_matches(".zip")
+                .trim_end_matches(".tar.gz"),
+        );
+
+        let java = find_java_executable(&jre_directory).ok_or_else(|| {
+            Error::NotFound(format!(
+                "Failed to find java executable in {}",
+                jre_directory.to_string_lossy()
+            ))
+        })?;
+
+        let test_java = check_java(&java)
+            .await?
+            .ok_or_else(|| Error::Generic("Java install check failed.".to_string()))?
+            .version;
+
+        if !test_java.starts_with(&java_vesrion) {
+            return Err(Error::Generic(format!(
+                "Java version returned was not what was expected. Found '{}' was expecting '{}'",
+                test_java, java_vesrion
+            )));
+        }
+
+        Ok((java_vesrion, java))
+    }
+}
@@ -1,46 +1,194 @@
+//! Catalogue of the minecraft versions from the launcher version manifest,
+//! and of the game and loader versions of the fabric and quilt meta servers.
+//!
+//! The manifest is kept on disk by [`offline::fetch_cached`] and in memory for [`REFRESH_INTERVAL`],
+//! so listing versions does not download it again and works while offline.
+//! The fabric and quilt lists are only kept on disk.
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use super::endpoints::Endpoint;
 use crate::error::{Error, Result};
-use serde::Deserialize;
+use crate::models::profile::Loader;
+use crate::offline;
+
+const LAUNCHER_META: &str = "/mc/game/version_manifest_v2.json";
+/// How long the manifest is used before asking the server for changes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
-const LAUNCHER_META: &str = "https://launchermeta.mojang.com/mc/game/version_manifest_v2.json";
+lazy_static::lazy_static! {
+    static ref VERSION_MANIFEST: Mutex<Option<(Instant, Arc<VersionManifest>)>> = Mutex::new(None);
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionType {
+    Release,
+    Snapshot,
+    OldBeta,
+    OldAlpha,
+    #[serde(other)]
+    Unknown,
+}
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct VersionManifestItem {
     pub id: String,
+    #[serde(rename = "type")]
+    pub version_type: VersionType,
     pub url: String,
-    // #[serde(alias = "type")]
-    //pub version_type: String,
-    // #[serde(alias = "releaseTime")]
-    // pub release_time: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub release_time: OffsetDateTime,
     pub sha1: String,
-    //#[serde(alias = "complianceLevel")]
-    //pub compliance_level: usize,
+    #[serde(default)]
+    pub compliance_level: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct LatestVersions {
+    pub release: String,
+    pub snapshot: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct VersionManifest {
+    pub latest: LatestVersions,
+    /// newest version first
+    pub versions: Vec<VersionManifestItem>,
+}
+
+/// Filter of [`VersionManifest::list`], fields that are not set match every version.
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct VersionFilter {
+    #[serde(default)]
+    pub types: Vec<VersionType>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub released_after: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub released_before: Option<OffsetDateTime>,
 }
 
-#[derive(Deserialize, Debug)]
-struct VersionManifestLastest {
-    release: String,
-    //snapshot: String
+impl VersionFilter {
+    pub fn matches(&self, version: &VersionManifestItem) -> bool {
+        (self.types.is_empty() || self.types.contains(&version.version_type))
+            && self
+                .released_after
+                .is_none_or(|after| version.release_time >= after)
+            && self
+                .released_before
+                .is_none_or(|before| version.release_time <= before)
+    }
 }
 
-#[derive(Deserialize, Debug)]
-struct VersionManifest {
-    latest: VersionManifestLastest,
-    versions: Vec<VersionManifestItem>,
+impl VersionManifest {
+    pub fn find(&self, id: &str) -> Option<&VersionManifestItem> {
+        self.versions.iter().find(|version| version.id == id)
+    }
+
+    pub fn latest_release(&self) -> Option<&VersionManifestItem> {
+        self.find(&self.latest.release)
+    }
+
+    pub fn latest_snapshot(&self) -> Option<&VersionManifestItem> {
+        self.find(&self.latest.snapshot)
+    }
+
+    /// Versions that match the filter, newest first
+    pub fn list<'a>(
+        &'a self,
+        filter: &'a VersionFilter,
+    ) -> impl Iterator<Item = &'a VersionManifestItem> + 'a {
+        self.versions
+            .iter()
+            .filter(move |version| filter.matches(version))
+    }
+
+    /// Copy of the manifest with only the versions that match the filter
+    pub fn filtered(&self, filter: &VersionFilter) -> VersionManifest {
+        VersionManifest {
+            latest: self.latest.clone(),
+            versions: self.list(filter).cloned().collect(),
+        }
+    }
 }
 
+/// Get the version manifest.
+/// The copy in memory is used until it is older than [`REFRESH_INTERVAL`] or `refresh` is set.
+pub async fn get_version_manifest(refresh: bool) -> Result<Arc<VersionManifest>> {
+    let mut cached = VERSION_MANIFEST.lock().await;
+
+    if !refresh
+        && let Some((fetched, manifest)) = cached.as_ref()
+        && fetched.elapsed() < REFRESH_INTERVAL
+    {
+        return Ok(manifest.clone());
+    }
+
+    let manifest = Arc::new(serde_json::from_str::<VersionManifest>(
+        &offline::fetch_cached(&Endpoint::LauncherMeta.url(LAUNCHER_META)).await?,
+    )?);
+    *cached = Some((Instant::now(), manifest.clone()));
+
+    Ok(manifest)
+}
+
+/// Get a version from the manifest, the latest release if no version is given
 pub async fn get_launcher_manifest(version: Option<&str>) -> Result<VersionManifestItem> {
-    let resp = reqwest::get(LAUNCHER_META)
-        .await?
-        .json::<VersionManifest>()
-        .await?;
+    let manifest = get_version_manifest(false).await?;
+
+    let data = match version {
+        Some(version) => manifest.find(version),
+        None => manifest.latest_release(),
+    };
 
-    let mc_version = version.unwrap_or(&resp.latest.release);
+    data.cloned()
+        .ok_or_else(|| Error::NotFound("minecraft version".to_string()))
+}
+
+/// A game or loader version of the fabric or quilt meta server
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LoaderMetaVersion {
+    pub version: String,
+    /// Not set for quilt loader versions
+    #[serde(default)]
+    pub stable: Option<bool>,
+}
 
-    if let Some(data) = resp.versions.iter().find(|x| x.id == mc_version) {
-        return Ok(data.to_owned());
+/// Meta server and api path of a loader
+fn loader_meta(loader: Loader) -> Result<(Endpoint, &'static str)> {
+    match loader {
+        Loader::Fabric => Ok((Endpoint::FabricMeta, "/v2/versions")),
+        Loader::Quilt => Ok((Endpoint::QuiltMeta, "/v3/versions")),
+        _ => Err(Error::Generic(format!(
+            "{} versions are not listed by a meta server",
+            loader
+        ))),
     }
+}
+
+async fn get_loader_meta(loader: Loader, list: &str) -> Result<Vec<LoaderMetaVersion>> {
+    let (endpoint, path) = loader_meta(loader)?;
+    let data = offline::fetch_cached(&endpoint.url(&format!("{}/{}", path, list))).await?;
 
-    Err(Error::NotFound("minecraft version".to_string()))
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Loader versions of fabric or quilt, newest first
+pub async fn get_loader_versions(loader: Loader) -> Result<Vec<LoaderMetaVersion>> {
+    get_loader_meta(loader, "loader").await
+}
+
+/// Minecraft versions that fabric or quilt can be installed for, newest first
+pub async fn get_loader_game_versions(loader: Loader) -> Result<Vec<LoaderMetaVersion>> {
+    get_loader_meta(loader, "game").await
 }
 
 #[cfg(test)]
@@ -48,6 +196,7 @@ mod tests {
     use super::*;
     //https://docs.rs/tokio/latest/tokio/attr.test.html
     #[tokio::test]
+    #[ignore = "needs network access"]
     async fn test_get_launcher_manifest() -> Result<()> {
         let version = "1.17.1".to_string();
 
@@ -62,4 +211,76 @@ mod tests {
 
         panic!("Failed to get manifest");
     }
+
+    #[test]
+    fn test_version_filter() {
+        let manifest = serde_json::from_str::<VersionManifest>(
+            r#"{
+                "latest": { "release": "1.21.4", "snapshot": "25w02a" },
+                "versions": [
+                    { "id": "25w02a", "type": "snapshot", "url": "", "time": "2025-01-08T13:20:46+00:00", "releaseTime": "2025-01-08T13:11:26+00:00", "sha1": "", "complianceLevel": 1 },
+                    { "id": "1.21.4", "type": "release", "url": "", "time": "2024-12-03T10:24:48+00:00", "releaseTime": "2024-12-03T10:12:57+00:00", "sha1": "", "complianceLevel": 1 },
+                    { "id": "1.20.1", "type": "release", "url": "", "time": "2023-06-12T13:25:51+00:00", "releaseTime": "2023-06-12T13:25:51+00:00", "sha1": "", "complianceLevel": 1 },
+                    { "id": "b1.7.3", "type": "old_beta", "url": "", "time": "2010-07-13T08:18:04+00:00", "releaseTime": "2011-07-07T22:00:00+00:00", "sha1": "" },
+                    { "id": "rd-132211", "type": "old_alpha", "url": "", "time": "2009-05-13T20:11:00+00:00", "releaseTime": "2009-05-13T20:11:00+00:00", "sha1": "" }
+                ]
+            }"#,
+        )
+        .expect("Failed to parse manifest");
+
+        assert_eq!(manifest.latest_release().unwrap().id, "1.21.4");
+        assert_eq!(manifest.latest_snapshot().unwrap().id, "25w02a");
+
+        let ids = |filter: VersionFilter| {
+            manifest
+                .list(&filter)
+                .map(|version| version.id.clone())
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            ids(VersionFilter {
+                types: vec![VersionType::Release],
+                ..Default::default()
+            }),
+            vec!["1.21.4", "1.20.1"]
+        );
+        assert_eq!(
+            ids(VersionFilter {
+                types: vec![VersionType::OldBeta, VersionType::OldAlpha],
+                ..Default::default()
+            }),
+            vec!["b1.7.3", "rd-132211"]
+        );
+        assert_eq!(
+            ids(VersionFilter {
+                released_after: Some(time::macros::datetime!(2024-01-01 0:00 UTC)),
+                ..Default::default()
+            }),
+            vec!["25w02a", "1.21.4"]
+        );
+        assert_eq!(
+            ids(VersionFilter {
+                types: vec![VersionType::Release],
+                released_before: Some(time::macros::datetime!(2024-01-01 0:00 UTC)),
+                ..Default::default()
+            }),
+            vec!["1.20.1"]
+        );
+    }
+
+    #[test]
+    fn test_loader_meta_version() {
+        let versions = serde_json::from_str::<Vec<LoaderMetaVersion>>(
+            r#"[
+                { "separator": ".", "build": 10, "maven": "net.fabricmc:fabric-loader:0.16.10", "version": "0.16.10", "stable": true },
+                { "separator": ".", "build": 1, "maven": "org.quiltmc:quilt-loader:0.27.0-beta.1", "version": "0.27.0-beta.1" }
+            ]"#,
+        )
+        .expect("Failed to parse versions");
+
+        assert_eq!(versions[0].stable, Some(true));
+        assert_eq!(versions[1].stable, None);
+        assert!(loader_meta(Loader::Forge).is_err());
+    }
 }
@@ -1,8 +1,14 @@
 use super::{
-    download::{download_assets, download_client, download_java, download_libraries},
-    fabric, forge,
+    download::{
+        download_assets, download_client, download_libraries, download_logging_config,
+    },
+    fabric, forge, jre,
     metadata::get_launcher_manifest,
-    neoforge, quilt, utils, InstallConfig,
+    neoforge,
+    progress::DownloadProgress,
+    quilt,
+    utils::{self, FileHash},
+    InstallConfig,
 };
 use crate::manifest::Manifest;
 use crate::models::profile::Loader;
@@ -20,6 +26,7 @@ use normalize_path::NormalizePath;
 /// - 20 Progress event
 ///     - 10 minecraft client/jre
 ///     - 10 modloader install
+/// - Transfer events while the jre and client files are downloaded
 /// - 1  Finished event
 pub async fn install_minecraft(
     config: InstallConfig,
@@ -49,7 +56,7 @@ pub async fn install_minecraft(
             &launcher_manifest.url,
             &client_manfiest_file,
             None,
-            Some(&launcher_manifest.sha1),
+            Some(FileHash::Sha1(&launcher_manifest.sha1)),
         )
         .await?;
     }
@@ -64,10 +71,12 @@ pub async fn install_minecraft(
     let manifset = Manifest::read_manifest(&client_manfiest_file, false).await?;
     let java_version = manifset
         .java_version
-        .ok_or(Error::NotFound("Java not found".to_string()))?
-        .major_version;
+        .ok_or(Error::NotFound("Java not found".to_string()))?;
 
-    let java_key = format!("java.{}", java_version);
+    // byte progress of the jre and game files
+    let progress = DownloadProgress::new(on_event.clone());
+
+    let java_key = format!("java.{}", java_version.major_version);
     let java_exe = if let Some(setting) = Setting::get(&java_key, db).await? {
         setting.value
     } else {
@@ -77,7 +86,9 @@ pub async fn install_minecraft(
                 message: Some("Installing Java".into()),
             })
             .map_err(|err| Error::Generic(err.to_string()))?;
-        let (build_version, path) = download_java(&runtime_directory, java_version).await?;
+        let (build_version, path) = progress
+            .scope(jre::install_java(&runtime_directory, &java_version, db))
+            .await?;
 
         let java_exe = path.to_string_lossy().to_string();
 
@@ -92,11 +103,17 @@ pub async fn install_minecraft(
         })
         .map_err(|err| Error::Generic(err.to_string()))?;
 
-    tokio::try_join! {
-        download_client(on_event, &config.version, &version_directory, manifset.downloads),
-        download_assets(on_event, &runtime_directory, manifset.asset_index),
-        download_libraries(on_event,&runtime_directory,&config.version,manifset.libraries)
-    }?;
+    progress
+        .scope(async {
+            tokio::try_join! {
+                download_client(on_event, &config.version, &version_directory, manifset.downloads),
+                download_assets(on_event, &runtime_directory, manifset.asset_index),
+                download_libraries(on_event,&runtime_directory,&config.version,manifset.libraries),
+                download_logging_config(&runtime_directory, manifset.logging)
+            }
+        })
+        .await?;
+    progress.finish();
 
     if config.loader != Loader::Vanilla {
         on_event
@@ -106,49 +123,55 @@ pub async fn install_minecraft(
             })
             .map_err(|err| Error::Generic(err.to_string()))?;
 
-        let version_id = match config.loader {
-            Loader::Vanilla => return Err(Error::Generic("Should not be here".to_string())),
-            Loader::Neoforge => {
-                neoforge::run_installer(
-                    on_event,
-                    &config.version,
-                    config.loader_version,
-                    &runtime_directory,
-                    &java_exe,
-                )
-                .await?
-            }
-            Loader::Forge => {
-                forge::run_installer(
-                    on_event,
-                    &config.version,
-                    config.loader_version,
-                    &runtime_directory,
-                    &java_exe,
-                )
-                .await?
-            }
-            Loader::Fabric => {
-                fabric::run_installer(
-                    on_event,
-                    &runtime_directory,
-                    &java_exe,
-                    &config.version,
-                    config.loader_version,
-                )
-                .await?
-            }
-            Loader::Quilt => {
-                quilt::run_installer(
-                    on_event,
-                    &runtime_directory,
-                    &java_exe,
-                    &config.version,
-                    config.loader_version,
-                )
-                .await?
-            }
-        };
+        // byte progress of the loader installer and its libraries
+        let version_id = progress
+            .scope(async {
+                match config.loader {
+                    Loader::Vanilla => Err(Error::Generic("Should not be here".to_string())),
+                    Loader::Neoforge => {
+                        neoforge::run_installer(
+                            on_event,
+                            &config.version,
+                            config.loader_version,
+                            &runtime_directory,
+                            &java_exe,
+                        )
+                        .await
+                    }
+                    Loader::Forge => {
+                        forge::run_installer(
+                            on_event,
+                            &config.version,
+                            config.loader_version,
+                            &runtime_directory,
+                            &java_exe,
+                        )
+                        .await
+                    }
+                    Loader::Fabric => {
+                        fabric::run_installer(
+                            on_event,
+                            &runtime_directory,
+                            &java_exe,
+                            &config.version,
+                            config.loader_version,
+                        )
+                        .await
+                    }
+                    Loader::Quilt => {
+                        quilt::run_installer(
+                            on_event,
+                            &runtime_directory,
+                            &java_exe,
+                            &config.version,
+                            config.loader_version,
+                        )
+                        .await
+                    }
+                }
+            })
+            .await?;
+        progress.finish();
 
         Ok(Some(version_id))
     } else {
@@ -1,15 +1,21 @@
 mod compression;
 pub mod content;
 mod download;
+pub mod endpoints;
 mod fabric;
 mod fabric_core;
 mod forge;
-mod metadata;
+pub mod http;
+mod jre;
+pub mod metadata;
 pub mod minecraft;
 mod neoforge;
+pub mod progress;
 mod quilt;
 pub mod utils;
 
+pub use jre::runtimes;
+
 use serde::{Deserialize, Serialize};
 
 use crate::models::profile::Loader;
@@ -31,4 +37,8 @@ impl InstallConfig {
             loader_version,
         }
     }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
 }
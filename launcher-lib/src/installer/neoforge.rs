@@ -3,16 +3,17 @@ use crate::{
     events::DownloadEvent,
     installer::download::download_libraries,
     manifest::Manifest,
+    offline,
 };
 use log::debug;
 use std::{path::Path, process::Stdio};
 use tokio::{fs, io::AsyncBufReadExt};
 
-const NEOFORGE_VERSION_LIST_URL: &str =
-    "https://maven.neoforged.net/releases/net/neoforged/neoforge/maven-metadata.xml";
+const NEOFORGE_VERSION_LIST: &str = "/net/neoforged/neoforge/maven-metadata.xml";
 const NEOFORGE_INSTALLER_FINISH_MESSAGE: &str =
     "You can delete this installer file now if you wish";
 
+use super::endpoints::Endpoint;
 use super::utils::{self};
 
 pub async fn get_latest_neoforge_version(minecraft_version: &str) -> Result<String> {
@@ -22,12 +23,7 @@ pub async fn get_latest_neoforge_version(minecraft_version: &str) -> Result<Stri
 
     let minor = if minor.is_empty() { "0" } else { minor };
 
-    let response = utils::REQUEST_CLIENT
-        .get(NEOFORGE_VERSION_LIST_URL)
-        .send()
-        .await?;
-
-    let data = response.text().await?;
+    let data = offline::fetch_cached(&Endpoint::NeoForgeMaven.url(NEOFORGE_VERSION_LIST)).await?;
 
     let regex = regex::Regex::new(&format!(
         r"<version>(?<loader_version>{}\.{}\.(?<value>\d+)(-beta)?)</version>",
@@ -75,7 +71,10 @@ pub async fn get_installer_download_url(
         get_latest_neoforge_version(minecraft).await?
     };
 
-    let url = format!("https://maven.neoforged.net/releases/net/neoforged/neoforge/{0}/neoforge-{0}-installer.jar",loader_version);
+    let url = Endpoint::NeoForgeMaven.url(&format!(
+        "/net/neoforged/neoforge/{0}/neoforge-{0}-installer.jar",
+        loader_version
+    ));
 
     Ok((loader_version, url))
 }
@@ -114,7 +113,7 @@ pub async fn run_installer(
 
     let file = tokio::fs::File::create_new(&log_file).await?;
 
-    utils::download_file(&download_url, &installer_path, None, None).await?;
+    utils::download_maven_file(&download_url, &installer_path).await?;
 
     let child = tokio::process::Command::new(java)
         .stdout(Stdio::piped())
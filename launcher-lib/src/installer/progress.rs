@@ -0,0 +1,226 @@
+//! Byte level progress of the downloads of a install.
+//!
+//! A [`DownloadProgress`] is set for a future with [`DownloadProgress::scope`],
+//! every `utils::download_file` call that runs in that future reports its bytes to it.
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::events::DownloadEvent;
+
+/// Min time between two transfer events
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+/// Weight of the newest measurement in the smoothed download rate
+const RATE_SMOOTHING: f64 = 0.3;
+
+tokio::task_local! {
+    static PROGRESS: DownloadProgress;
+}
+
+#[derive(Clone)]
+pub struct DownloadProgress(Arc<Inner>);
+
+struct Inner {
+    on_event: tauri::ipc::Channel<DownloadEvent>,
+    bytes_done: AtomicU64,
+    bytes_total: AtomicU64,
+    files_done: AtomicUsize,
+    files_total: AtomicUsize,
+    rate: Mutex<Rate>,
+}
+
+#[derive(Default)]
+struct Rate {
+    last_emit: Option<Instant>,
+    last_bytes: u64,
+    bytes_per_second: f64,
+}
+
+impl DownloadProgress {
+    pub fn new(on_event: tauri::ipc::Channel<DownloadEvent>) -> Self {
+        Self(Arc::new(Inner {
+            on_event,
+            bytes_done: AtomicU64::new(0),
+            bytes_total: AtomicU64::new(0),
+            files_done: AtomicUsize::new(0),
+            files_total: AtomicUsize::new(0),
+            rate: Mutex::new(Rate::default()),
+        }))
+    }
+
+    /// Run a future with this progress receiving the downloads made in it.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        PROGRESS.scope(self.clone(), future).await
+    }
+
+    /// The progress of the current scope
+    pub(crate) fn current() -> Option<Self> {
+        PROGRESS.try_with(Clone::clone).ok()
+    }
+
+    /// Add expected bytes and files to the totals of the current scope.
+    pub(crate) fn expect(bytes: u64, files: usize) {
+        if let Some(progress) = Self::current() {
+            progress.add_total(bytes, files);
+        }
+    }
+
+    pub fn add_total(&self, bytes: u64, files: usize) {
+        self.0.bytes_total.fetch_add(bytes, Ordering::Relaxed);
+        self.0.files_total.fetch_add(files, Ordering::Relaxed);
+    }
+
+    fn add_bytes(&self, bytes: u64) {
+        self.0.bytes_done.fetch_add(bytes, Ordering::Relaxed);
+        self.emit(false);
+    }
+
+    fn remove_bytes(&self, bytes: u64) {
+        self.0.bytes_done.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    fn file_done(&self) {
+        self.0.files_done.fetch_add(1, Ordering::Relaxed);
+        self.emit(false);
+    }
+
+    /// Send the final state, ignoring the throttle.
+    pub fn finish(&self) {
+        self.emit(true);
+    }
+
+    fn emit(&self, force: bool) {
+        let now = Instant::now();
+        let mut rate = self.0.rate.lock().unwrap_or_else(|err| err.into_inner());
+        if !force
+            && rate
+                .last_emit
+                .is_some_and(|last| now.duration_since(last) < EMIT_INTERVAL)
+        {
+            return;
+        }
+
+        let bytes_done = self.0.bytes_done.load(Ordering::Relaxed);
+        if let Some(last) = rate.last_emit {
+            let elapsed = now.duration_since(last).as_secs_f64();
+            if elapsed > 0.0 {
+                let current = bytes_done.saturating_sub(rate.last_bytes) as f64 / elapsed;
+                rate.bytes_per_second = if rate.bytes_per_second == 0.0 {
+                    current
+                } else {
+                    RATE_SMOOTHING * current + (1.0 - RATE_SMOOTHING) * rate.bytes_per_second
+                };
+            }
+        }
+        rate.last_emit = Some(now);
+        rate.last_bytes = bytes_done;
+
+        // files without a size in the manifest are only known once they are downloaded
+        let bytes_total = self.0.bytes_total.load(Ordering::Relaxed).max(bytes_done);
+        let files_done = self.0.files_done.load(Ordering::Relaxed);
+        let files_total = self.0.files_total.load(Ordering::Relaxed).max(files_done);
+
+        let eta_seconds = (rate.bytes_per_second >= 1.0)
+            .then(|| ((bytes_total - bytes_done) as f64 / rate.bytes_per_second).ceil() as u64);
+
+        if let Err(err) = self.0.on_event.send(DownloadEvent::Transfer {
+            bytes_done,
+            bytes_total,
+            files_done,
+            files_total,
+            bytes_per_second: rate.bytes_per_second as u64,
+            eta_seconds,
+        }) {
+            log::error!("Failed to send transfer progress: {}", err);
+        }
+    }
+}
+
+/// Bytes of a single file that are counted in the progress of the current scope.
+/// A download that restarts from zero takes its bytes back out of the progress.
+pub(crate) struct FileProgress {
+    progress: Option<DownloadProgress>,
+    counted: u64,
+}
+
+impl FileProgress {
+    pub fn current() -> Self {
+        Self {
+            progress: DownloadProgress::current(),
+            counted: 0,
+        }
+    }
+
+    /// Set the bytes of the file that are on disk
+    pub fn set(&mut self, bytes: u64) {
+        if let Some(progress) = &self.progress {
+            if bytes >= self.counted {
+                progress.add_bytes(bytes - self.counted);
+            } else {
+                progress.remove_bytes(self.counted - bytes);
+            }
+        }
+        self.counted = bytes;
+    }
+
+    pub fn done(&self) {
+        if let Some(progress) = &self.progress {
+            progress.file_done();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::ipc::{Channel, InvokeResponseBody};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_transfer_events() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let channel = Channel::new(move |body| {
+            if let InvokeResponseBody::Json(json) = body {
+                tx.send(json).expect("Failed to send event");
+            }
+            Ok(())
+        });
+
+        let progress = DownloadProgress::new(channel);
+        progress
+            .scope(async {
+                DownloadProgress::expect(2048, 2);
+
+                let mut file = FileProgress::current();
+                file.set(1024);
+                // throttled
+                file.set(1536);
+
+                tokio::time::advance(Duration::from_secs(1)).await;
+                // restarted from zero
+                file.set(0);
+                file.set(1024);
+                file.done();
+            })
+            .await;
+        progress.finish();
+
+        let events = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|json| serde_json::from_str::<serde_json::Value>(&json).expect("Invalid json"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["event"], "transfer");
+        assert_eq!(events[0]["data"]["bytes_done"], 1024);
+
+        // the bytes of the restarted download are not counted twice
+        assert_eq!(events[1]["data"]["bytes_done"], 1024);
+        assert_eq!(events[1]["data"]["bytes_per_second"], 0);
+
+        let data = &events[2]["data"];
+        assert_eq!(data["bytes_total"], 2048);
+        assert_eq!(data["files_done"], 1);
+        assert_eq!(data["files_total"], 2);
+    }
+}
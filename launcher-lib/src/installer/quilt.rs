@@ -1,12 +1,14 @@
-use crate::{error::Result, events::DownloadEvent, installer::fabric_core};
+use crate::{
+    error::Result,
+    events::DownloadEvent,
+    installer::{endpoints::Endpoint, fabric_core},
+};
 use normalize_path::NormalizePath;
 use std::path::Path;
 
-const QUILT_LOADER_VERSION_LIST_URL: &str = "https://meta.quiltmc.org/v3/versions/loader";
-const QUILT_INSTALLER_LIST_URL: &str =
-    "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/maven-metadata.xml";
-const QUILT_INSTALLER_DOWNLOAD: &str =
-    "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/";
+const QUILT_LOADER_VERSION_LIST: &str = "/v3/versions/loader";
+const QUILT_INSTALLER_LIST: &str = "/org/quiltmc/quilt-installer/maven-metadata.xml";
+const QUILT_INSTALLER_DOWNLOAD: &str = "/org/quiltmc/quilt-installer/";
 
 /// Install the quilt
 pub async fn run_installer(
@@ -24,13 +26,15 @@ pub async fn run_installer(
     let loader_version = if let Some(version) = loader_version {
         version
     } else {
-        fabric_core::get_latest_loader_version(QUILT_LOADER_VERSION_LIST_URL).await?
+        fabric_core::get_latest_loader_version(&Endpoint::QuiltMeta.url(QUILT_LOADER_VERSION_LIST))
+            .await?
     };
-    let installer_version = fabric_core::get_latest_installer(QUILT_INSTALLER_LIST_URL).await?;
-    let installer_url = format!(
+    let installer_version =
+        fabric_core::get_latest_installer(&Endpoint::QuiltMaven.url(QUILT_INSTALLER_LIST)).await?;
+    let installer_url = Endpoint::QuiltMaven.url(&format!(
         "{0}{1}/quilt-installer-{1}.jar",
         QUILT_INSTALLER_DOWNLOAD, installer_version
-    );
+    ));
 
     let dir = format!("--install-dir={}", runtime_directory.to_string_lossy());
     let modded_version = format!("quilt-loader-{}-{}", loader_version, version);
@@ -1,74 +1,427 @@
 use log::{debug, warn};
+use reqwest::StatusCode;
+use serde::Serialize;
 use sha1::{Digest, Sha1};
-use std::path::Path;
+use sha2::{Sha256, Sha512};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
-use tokio::fs::{File, create_dir_all};
+use tokio::fs::{OpenOptions, create_dir_all};
 use tokio::io::AsyncWriteExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
 
+use super::progress::{DownloadProgress, FileProgress};
+use super::{endpoints, http};
+use crate::database::RwDatabase;
 use crate::error::{Error, Result};
+use crate::models::setting::Setting;
+use crate::offline;
+
+const FETCH_ATTEMPTS: usize = 5;
 
 lazy_static::lazy_static! {
-    pub static ref REQUEST_CLIENT: reqwest::Client = {
-        let mut headers = reqwest::header::HeaderMap::new();
-        let header = reqwest::header::HeaderValue::from_str(
-            &format!("VisualSource/rusty-mc-launcher/{}",env!("CARGO_PKG_VERSION"))
-        ).expect("Failed to construct http client user agent");
-
-        headers.insert(reqwest::header::USER_AGENT, header);
-
-        reqwest::Client::builder()
-        .tcp_keepalive(Some(std::time::Duration::from_secs(10)))
-        .default_headers(headers)
-        .build()
-        .expect("Request client construct failed.")
-    };
+    static ref DOWNLOAD_SCHEDULER: RwLock<Arc<DownloadScheduler>> =
+        RwLock::new(Arc::new(DownloadScheduler::new(DownloadLimits::default())));
+}
+
+/// Limits that all downloads share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadLimits {
+    /// Max open connections, from `option.download_connections`
+    pub connections: usize,
+    /// Max open connections to one host, from `option.download_host_connections`
+    pub host_connections: usize,
+    /// Max bytes per second of all downloads, from `option.download_bandwidth` in KB/s.
+    /// Not limited if not set or 0.
+    pub bytes_per_second: Option<u64>,
+}
+
+impl Default for DownloadLimits {
+    fn default() -> Self {
+        Self {
+            connections: 24,
+            host_connections: 8,
+            bytes_per_second: None,
+        }
+    }
+}
+
+impl DownloadLimits {
+    pub async fn from_settings(db: &RwDatabase) -> Result<Self> {
+        let default = Self::default();
+
+        let connections = Setting::get_as_u64("option.download_connections", db)
+            .await?
+            .map_or(default.connections, |value| value.max(1) as usize);
+        let host_connections = Setting::get_as_u64("option.download_host_connections", db)
+            .await?
+            .map_or(default.host_connections, |value| value.max(1) as usize);
+        let bytes_per_second = Setting::get_as_u64("option.download_bandwidth", db)
+            .await?
+            .filter(|value| *value > 0)
+            .map(|value| value * 1024);
+
+        Ok(Self {
+            connections,
+            host_connections: host_connections.min(connections),
+            bytes_per_second,
+        })
+    }
+}
+
+/// Caps the connections and bandwidth of every download that goes through `download_file`,
+/// no matter how many tasks are downloading at the same time.
+#[derive(Debug)]
+pub struct DownloadScheduler {
+    limits: DownloadLimits,
+    connections: Arc<Semaphore>,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+    /// When the bandwidth limit allows the next chunk to be read
+    next_read: Mutex<Instant>,
+}
+
+/// Held while a connection is open
+struct DownloadPermit {
+    _host: OwnedSemaphorePermit,
+    _connection: OwnedSemaphorePermit,
+}
+
+impl DownloadScheduler {
+    pub fn new(limits: DownloadLimits) -> Self {
+        Self {
+            connections: Arc::new(Semaphore::new(limits.connections)),
+            hosts: Mutex::new(HashMap::new()),
+            next_read: Mutex::new(Instant::now()),
+            limits,
+        }
+    }
+
+    /// The scheduler that is used by `download_file`
+    pub fn shared() -> Arc<Self> {
+        DOWNLOAD_SCHEDULER
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Replace the shared scheduler if the limits changed.
+    /// Downloads that already started finish under the old limits.
+    pub fn configure(limits: DownloadLimits) {
+        let mut scheduler = DOWNLOAD_SCHEDULER
+            .write()
+            .unwrap_or_else(|err| err.into_inner());
+        if scheduler.limits != limits {
+            log::debug!("Using download limits {:?}", limits);
+            *scheduler = Arc::new(Self::new(limits));
+        }
+    }
+
+    /// Load the limits from the settings and configure the shared scheduler with them.
+    pub async fn configure_from_settings(db: &RwDatabase) -> Result<()> {
+        Self::configure(DownloadLimits::from_settings(db).await?);
+        Ok(())
+    }
+
+    /// Wait for a free connection to the host of the url.
+    /// The host slot is taken first so a busy host does not hold global slots that other hosts could use.
+    async fn acquire(&self, url: &str) -> Result<DownloadPermit> {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_string()))
+            .unwrap_or_default();
+
+        let host = self
+            .hosts
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limits.host_connections)))
+            .clone();
+
+        let host = host
+            .acquire_owned()
+            .await
+            .map_err(|err| Error::Generic(err.to_string()))?;
+        let connection = self
+            .connections
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|err| Error::Generic(err.to_string()))?;
+
+        Ok(DownloadPermit {
+            _host: host,
+            _connection: connection,
+        })
+    }
+
+    /// Wait until the bandwidth limit allows more bytes to be read.
+    async fn throttle(&self, bytes: usize) {
+        let Some(bytes_per_second) = self.limits.bytes_per_second else {
+            return;
+        };
+
+        let wait_until = {
+            let mut next_read = self.next_read.lock().unwrap_or_else(|err| err.into_inner());
+            let start = (*next_read).max(Instant::now());
+            *next_read = start + Duration::from_secs_f64(bytes as f64 / bytes_per_second as f64);
+            start
+        };
+
+        tokio::time::sleep_until(wait_until).await;
+    }
+}
+
+/// Digest algorithms files can be verified with, ordered from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    /// Length of the hex digest
+    fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Sha256 => 64,
+            HashAlgorithm::Sha512 => 128,
+        }
+    }
+
+    /// Hex digest of a file, this blocks while the file is read.
+    pub fn digest_file(self, path: &Path) -> Result<String> {
+        fn digest<D: Digest + std::io::Write>(path: &Path) -> Result<String> {
+            let mut file = std::fs::File::open(path)?;
+            let mut hasher = D::new();
+            let size = std::io::copy(&mut file, &mut hasher)?;
+            debug!("File at {:?} has size of {} on disk", path, size);
+            Ok(hex::encode(hasher.finalize()))
+        }
+
+        match self {
+            HashAlgorithm::Sha1 => digest::<Sha1>(path),
+            HashAlgorithm::Sha256 => digest::<Sha256>(path),
+            HashAlgorithm::Sha512 => digest::<Sha512>(path),
+        }
+    }
+}
+
+/// Expected hex digest of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileHash<'a> {
+    Sha1(&'a str),
+    Sha256(&'a str),
+    Sha512(&'a str),
+}
+
+impl<'a> FileHash<'a> {
+    pub fn new(algorithm: HashAlgorithm, value: &'a str) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha1 => FileHash::Sha1(value),
+            HashAlgorithm::Sha256 => FileHash::Sha256(value),
+            HashAlgorithm::Sha512 => FileHash::Sha512(value),
+        }
+    }
+
+    pub fn algorithm(self) -> HashAlgorithm {
+        match self {
+            FileHash::Sha1(_) => HashAlgorithm::Sha1,
+            FileHash::Sha256(_) => HashAlgorithm::Sha256,
+            FileHash::Sha512(_) => HashAlgorithm::Sha512,
+        }
+    }
+
+    pub fn value(self) -> &'a str {
+        match self {
+            FileHash::Sha1(value) | FileHash::Sha256(value) | FileHash::Sha512(value) => value,
+        }
+    }
+
+    /// The strongest of the given digests
+    pub fn strongest(hashes: impl IntoIterator<Item = FileHash<'a>>) -> Option<Self> {
+        hashes.into_iter().max_by_key(|hash| hash.algorithm())
+    }
+
+    /// Check the file at path against this digest
+    pub async fn verify(self, path: &Path) -> Result<bool> {
+        let digest = hash_file(path, self.algorithm()).await?;
+        Ok(digest.eq_ignore_ascii_case(self.value()))
+    }
+}
+
+/// Hex digest of a file, hashed on a blocking thread.
+pub async fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || algorithm.digest_file(&path))
+        .await
+        .map_err(|err| Error::Generic(err.to_string()))?
+}
+
+/// Get the strongest checksum that a maven repository publishes next to a file, ex: `<url>.sha512`
+pub async fn maven_checksum(url: &str) -> Option<(HashAlgorithm, String)> {
+    for algorithm in [
+        HashAlgorithm::Sha512,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha1,
+    ] {
+        let sidecar = endpoints::rewrite(&format!("{}.{}", url, algorithm.name()));
+        let Ok(response) = http::client().get(&sidecar).send().await else {
+            continue;
+        };
+        let Ok(body) = response.error_for_status().map(|response| response.text()) else {
+            continue;
+        };
+        let Ok(body) = body.await else {
+            continue;
+        };
+
+        // some repositories put the file name after the digest
+        if let Some(digest) = body.split_whitespace().next()
+            && digest.len() == algorithm.hex_len()
+            && digest.bytes().all(|byte| byte.is_ascii_hexdigit())
+        {
+            return Some((algorithm, digest.to_string()));
+        }
+    }
+
+    None
 }
-const FETCH_ATTEMPTS: usize = 5;
 
 /// Gets the sha1 hash of a file.
 pub async fn get_file_hash(path: &Path) -> Result<String> {
-    let mut file = File::open(path)
-        .await?
-        .try_into_std()
-        .map_err(|_| Error::Generic("Failed to open file as std".to_string()))?;
-    let mut hasher = Sha1::new();
-    let size = std::io::copy(&mut file, &mut hasher)?;
-    let file_hash = hasher.finalize();
-    debug!("File at {:?} has size of {} on disk", path, size);
+    hash_file(path, HashAlgorithm::Sha1).await
+}
 
-    Ok(hex::encode(file_hash))
+/// A file that could not be downloaded after every attempt.
+/// Only sent to the frontend to show what failed, a retry installs the whole queue item again.
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadError {
+    pub url: String,
+    pub path: PathBuf,
+    pub attempts: usize,
+    /// Http status of the last failed response
+    pub status: Option<u16>,
+    /// Algorithm of the expected digest
+    pub hash_algorithm: Option<HashAlgorithm>,
+    /// Digest the file was expected to have
+    pub expected_hash: Option<String>,
+    /// Digest of the last downloaded file when it did not match the expected one
+    pub actual_hash: Option<String>,
+    /// Error of the last attempt
+    pub reason: String,
+}
+
+impl DownloadError {
+    fn new(url: &str, path: &Path, hash: Option<FileHash<'_>>) -> Self {
+        Self {
+            url: url.to_string(),
+            path: path.to_path_buf(),
+            attempts: 0,
+            status: None,
+            hash_algorithm: hash.map(|hash| hash.algorithm()),
+            expected_hash: hash.map(|hash| hash.value().to_string()),
+            actual_hash: None,
+            reason: String::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} to {} failed after {} attempts: {}",
+            self.url,
+            self.path.to_string_lossy(),
+            self.attempts,
+            self.reason
+        )?;
+        if let Some(status) = self.status {
+            write!(f, " (status {})", status)?;
+        }
+        if let (Some(algorithm), Some(expected), Some(actual)) =
+            (self.hash_algorithm, &self.expected_hash, &self.actual_hash)
+        {
+            write!(
+                f,
+                " ({} expected {} got {})",
+                algorithm.name(),
+                expected,
+                actual
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Collect the results of concurrent downloads.
+/// Every failed download is gathered into a single [`Error::Downloads`],
+/// other errors are only returned when no download failed.
+pub fn collect_downloads<T>(results: impl IntoIterator<Item = Result<T>>) -> Result<Vec<T>> {
+    let mut values = Vec::new();
+    let mut failed = Vec::new();
+    let mut other = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(Error::Download(failure)) => failed.push(*failure),
+            Err(Error::Downloads(failures)) => failed.extend(failures),
+            Err(err) => other.push(err),
+        }
+    }
+
+    if failed.is_empty() {
+        return match other.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(values),
+        };
+    }
+
+    for err in &other {
+        log::error!("{}", err);
+    }
+    Err(Error::Downloads(failed))
 }
 
 /// Tries to download file from source.
-/// If as sha1 hash is given, the output location is checked if the file exists and checks if hash
+/// If as hash is given, the output location is checked if the file exists and checks if hash
 /// matches file at that location and does not try to download from remote source if hash matches
+///
+/// The file is downloaded to a `.part` file next to the output, which is resumed on retry when the server supports range requests.
+/// It is only moved to the output after the hash is verified.
 pub async fn download_file(
     source_url: &str,
     output_directory: &Path,
     auth: Option<&str>,
-    sha1: Option<&str>,
+    hash: Option<FileHash<'_>>,
 ) -> Result<()> {
-    if let Some(parent) = output_directory.parent() {
-        if !parent.exists() {
-            create_dir_all(&parent).await?;
-        }
+    if let Some(parent) = output_directory.parent()
+        && !parent.exists()
+    {
+        create_dir_all(&parent).await?;
     }
 
     if output_directory.exists() && output_directory.is_file() {
-        if let Some(hash) = sha1 {
-            log::debug!("File exists and has sha1 hash");
-            let mut file = File::open(&output_directory)
-                .await?
-                .try_into_std()
-                .map_err(|_| Error::Generic("io error".to_string()))?;
-            let mut hasher = Sha1::new();
-
-            let size = std::io::copy(&mut file, &mut hasher)?;
-
-            let file_hash = hasher.finalize();
-
-            log::debug!("Current size of file on disk: {}", size);
-            if hex::encode(file_hash) == hash {
+        if let Some(hash) = hash {
+            log::debug!("File exists and has {} hash", hash.algorithm().name());
+            if hash.verify(output_directory).await? {
+                let mut file_progress = FileProgress::current();
+                file_progress.set(tokio::fs::metadata(output_directory).await?.len());
+                file_progress.done();
                 return Ok(());
             }
         } else {
@@ -84,8 +437,22 @@ pub async fn download_file(
         return Err(Error::NotFound("No download url was provided".to_string()));
     }
 
-    for attempt in 1..=(FETCH_ATTEMPTS + 1) {
+    if offline::is_forced() {
+        return Err(Error::Offline(source_url.to_string()));
+    }
+
+    let part = part_path(output_directory);
+    // a part left by a earlier run is tried, servers that ignore the range header send the whole file
+    let mut resume = part.is_file();
+    let mut file_progress = FileProgress::current();
+    let mut failure = DownloadError::new(source_url, output_directory, hash);
+
+    for attempt in 1..=FETCH_ATTEMPTS {
         if attempt > 1 {
+            // offline mode was turned on while retrying, the download is postponed instead
+            if offline::is_forced() {
+                break;
+            }
             log::warn!(
                 "Fetch Attempt {} | Duration {}ms | Task {}",
                 attempt,
@@ -94,44 +461,268 @@ pub async fn download_file(
             );
             tokio::time::sleep(Duration::from_millis(15_000 * (attempt as u64))).await;
         }
+        failure.attempts = attempt;
+
+        if let Err(err) = fetch_part(
+            source_url,
+            &part,
+            auth,
+            hash.is_some(),
+            &mut resume,
+            &mut file_progress,
+        )
+        .await
+        {
+            warn!("Failed to download {}: {}", source_url, err);
+            let status = match &err {
+                Error::Http(err) => err.status(),
+                _ => None,
+            };
+            failure.status = status.map(|status| status.as_u16());
+            failure.actual_hash = None;
+            failure.reason = err.to_string();
+
+            // the server refused the request, every other attempt would get the same answer
+            if status.is_some_and(|status| {
+                status.is_client_error()
+                    && status != StatusCode::REQUEST_TIMEOUT
+                    && status != StatusCode::TOO_MANY_REQUESTS
+            }) {
+                break;
+            }
+            continue;
+        }
+
+        if let Some(hash) = hash {
+            let digest = hash_file(&part, hash.algorithm()).await?;
+            if !digest.eq_ignore_ascii_case(hash.value()) {
+                warn!(
+                    "Downloaded file {} failed {} check",
+                    source_url,
+                    hash.algorithm().name()
+                );
+                tokio::fs::remove_file(&part).await?;
+                remove_validator(&part).await?;
+                file_progress.set(0);
+                resume = false;
+                failure.status = None;
+                failure.actual_hash = Some(digest);
+                failure.reason = format!("Failed {} check", hash.algorithm().name());
+                continue;
+            }
+        }
+
+        tokio::fs::rename(&part, output_directory).await?;
+        remove_validator(&part).await?;
+        file_progress.done();
+
+        return Ok(());
+    }
+
+    Err(Error::Download(Box::new(failure)))
+}
+
+/// Download a file from a maven repository, verified with the checksum the repository publishes for it.
+pub async fn download_maven_file(source_url: &str, output: &Path) -> Result<()> {
+    let checksum = maven_checksum(source_url).await;
+    if checksum.is_none() {
+        warn!("No checksum was found for {}", source_url);
+    }
+
+    // maven repositories do not publish the size, it is known once the download started
+    DownloadProgress::expect(0, 1);
+
+    download_file(
+        source_url,
+        output,
+        None,
+        checksum
+            .as_ref()
+            .map(|(algorithm, value)| FileHash::new(*algorithm, value)),
+    )
+    .await
+}
+
+/// Path of the partial download of a file, ex: client.jar.part
+fn part_path(output: &Path) -> PathBuf {
+    let mut file_name = output
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".part");
+    output.with_file_name(file_name)
+}
+
+/// The `ETag` or `Last-Modified` of the response a part file was started from, stored next to it
+fn validator_path(part: &Path) -> PathBuf {
+    let mut path = part.as_os_str().to_owned();
+    path.push(".validator");
+    PathBuf::from(path)
+}
+
+async fn remove_validator(part: &Path) -> Result<()> {
+    match tokio::fs::remove_file(validator_path(part)).await {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Value for a `If-Range` header, weak etags can not be used for range requests
+fn range_validator(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+    };
+
+    header(reqwest::header::ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(reqwest::header::LAST_MODIFIED))
+        .map(str::to_string)
+}
+
+/// Full length of the file from a `Content-Range: bytes */<length>` header
+fn content_range_length(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit_once('/')?
+        .1
+        .parse()
+        .ok()
+}
+
+/// Download a file into its part file.
+/// Continues from the end of the part file with a range request if `resume` is set,
+/// `resume` is updated with whether the server advertises `Accept-Ranges`.
+///
+/// A resume sends the validator of the response the part was started from as `If-Range`,
+/// so the server sends the whole file again if it changed.
+/// A part without a validator is only resumed when the file is `verified` with a hash afterwards.
+async fn fetch_part(
+    source_url: &str,
+    part: &Path,
+    auth: Option<&str>,
+    verified: bool,
+    resume: &mut bool,
+    file_progress: &mut FileProgress,
+) -> Result<()> {
+    let mut offset = if *resume {
+        tokio::fs::metadata(part)
+            .await
+            .map(|meta| meta.len())
+            .unwrap_or_default()
+    } else {
+        0
+    };
+
+    let validator = if offset > 0 {
+        tokio::fs::read_to_string(validator_path(part)).await.ok()
+    } else {
+        None
+    };
+    if offset > 0 && validator.is_none() && !verified {
+        debug!(
+            "Part of {} can not be checked for changes, restarting download",
+            source_url
+        );
+        offset = 0;
+    }
+
+    let url = endpoints::rewrite(source_url);
+    let mut req = http::client().request(reqwest::Method::GET, &url);
+    if let Some(auth_token) = auth {
+        req = req.header(reqwest::header::AUTHORIZATION, auth_token);
+    }
+    if offset > 0 {
+        req = req.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        if let Some(validator) = &validator {
+            req = req.header(reqwest::header::IF_RANGE, validator);
+        }
+    }
+
+    let scheduler = DownloadScheduler::shared();
+    let _permit = scheduler.acquire(&url).await?;
 
-        let mut req = REQUEST_CLIENT.request(reqwest::Method::GET, source_url);
+    let response = req.send().await?;
+    *resume = response
+        .headers()
+        .get(reqwest::header::ACCEPT_RANGES)
+        .is_some_and(|value| value.as_bytes() == b"bytes");
 
-        if let Some(auth_token) = auth {
-            req = req.header(reqwest::header::AUTHORIZATION, auth_token);
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // the part file already has every byte
+        if content_range_length(response.headers()) == Some(offset) {
+            file_progress.set(offset);
+            return Ok(());
         }
-        let response = req.send().await;
-        match response {
-            Ok(mut result) => match File::create(&output_directory).await {
-                Ok(mut file) => {
-                    let mut hasher = Sha1::new();
-                    while let Some(mut chunk) = result.chunk().await? {
-                        hasher.update(&chunk);
-                        file.write_all_buf(&mut chunk).await?;
-                    }
 
-                    file.shutdown().await?;
+        tokio::fs::remove_file(part).await?;
+        remove_validator(part).await?;
+        file_progress.set(0);
+        *resume = false;
+        return Err(Error::Generic(format!(
+            "Part of {} does not match the remote file",
+            source_url
+        )));
+    }
 
-                    if let Some(hash) = sha1 {
-                        let result = hex::encode(hasher.finalize());
-                        if result != hash {
-                            if attempt <= 3 {
-                                continue;
-                            }
-                            return Err(Error::Sha1Error);
-                        }
-                    }
+    let mut response = response.error_for_status()?;
 
-                    return Ok(());
-                }
-                Err(_) if attempt <= 3 => continue,
-                Err(err) => return Err(err.into()),
-            },
-            Err(_) if attempt <= 3 => continue,
-            Err(err) => return Err(err.into()),
+    let append = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    if offset > 0 && !append {
+        debug!(
+            "Range request was ignored, restarting download of {}",
+            source_url
+        );
+    }
+
+    let expected = response
+        .content_length()
+        .map(|length| if append { length + offset } else { length });
+    let mut written = if append { offset } else { 0 };
+    file_progress.set(written);
+
+    // a new part is started, the validator of this response is used to resume it
+    if !append {
+        match range_validator(response.headers()) {
+            Some(validator) => tokio::fs::write(validator_path(part), validator).await?,
+            None => remove_validator(part).await?,
         }
     }
 
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(part)
+        .await?;
+
+    let streamed = loop {
+        match response.chunk().await {
+            Ok(Some(mut chunk)) => {
+                scheduler.throttle(chunk.len()).await;
+                written += chunk.len() as u64;
+                file.write_all_buf(&mut chunk).await?;
+                file_progress.set(written);
+            }
+            Ok(None) => break Ok(()),
+            Err(err) => break Err(err),
+        }
+    };
+    // keep what was received so the next attempt can resume from it
+    file.shutdown().await?;
+    streamed?;
+
+    if let Some(expected) = expected.filter(|expected| *expected != written) {
+        return Err(Error::Generic(format!(
+            "Download of {} ended after {} of {} bytes",
+            source_url, written, expected
+        )));
+    }
+
     Ok(())
 }
 
@@ -147,12 +738,167 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs network access"]
     async fn test_download() {
         init();
         let dir = std::env::temp_dir().join("test.jar");
         log::info!("{}", dir.to_string_lossy());
-        download_file("https://piston-data.mojang.com/v1/objects/05b6f1c6b46a29d6ea82b4e0d42190e42402030f/client.jar", &dir, None, Some("05b6f1c6b46a29d6ea82b4e0d42190e42402030f"))
+        download_file("https://piston-data.mojang.com/v1/objects/05b6f1c6b46a29d6ea82b4e0d42190e42402030f/client.jar", &dir, None, Some(FileHash::Sha1("05b6f1c6b46a29d6ea82b4e0d42190e42402030f")))
             .await
             .expect("Failed to download");
     }
+
+    #[tokio::test]
+    async fn test_scheduler_host_limit() {
+        let scheduler = DownloadScheduler::new(DownloadLimits {
+            connections: 4,
+            host_connections: 1,
+            bytes_per_second: None,
+        });
+
+        let first = scheduler
+            .acquire("https://libraries.minecraft.net/a.jar")
+            .await
+            .expect("Failed to get permit");
+
+        // other hosts are not blocked by a busy host
+        tokio::time::timeout(
+            Duration::from_millis(100),
+            scheduler.acquire("https://resources.download.minecraft.net/b"),
+        )
+        .await
+        .expect("Other host was blocked")
+        .expect("Failed to get permit");
+
+        assert!(
+            tokio::time::timeout(
+                Duration::from_millis(100),
+                scheduler.acquire("https://libraries.minecraft.net/c.jar"),
+            )
+            .await
+            .is_err()
+        );
+
+        drop(first);
+        assert_eq!(scheduler.connections.available_permits(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_bandwidth() {
+        let scheduler = DownloadScheduler::new(DownloadLimits {
+            bytes_per_second: Some(1024),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            scheduler.throttle(512).await;
+        }
+
+        // the first chunk is read right away, the other three wait 0.5s each
+        assert_eq!(start.elapsed(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(Path::new("libraries/client.jar")),
+            Path::new("libraries/client.jar.part")
+        );
+        assert_eq!(
+            validator_path(Path::new("libraries/client.jar.part")),
+            Path::new("libraries/client.jar.part.validator")
+        );
+    }
+
+    #[test]
+    fn test_range_headers() {
+        use reqwest::header::{CONTENT_RANGE, ETAG, HeaderMap, HeaderValue, LAST_MODIFIED};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(range_validator(&headers), None);
+
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2026 07:28:00 GMT"),
+        );
+        headers.insert(ETAG, HeaderValue::from_static("W/\"weak\""));
+        assert_eq!(
+            range_validator(&headers).as_deref(),
+            Some("Wed, 21 Oct 2026 07:28:00 GMT")
+        );
+
+        headers.insert(ETAG, HeaderValue::from_static("\"strong\""));
+        assert_eq!(range_validator(&headers).as_deref(), Some("\"strong\""));
+
+        assert_eq!(content_range_length(&headers), None);
+        headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes */1024"));
+        assert_eq!(content_range_length(&headers), Some(1024));
+    }
+
+    #[test]
+    fn test_collect_downloads() {
+        let failure = |name: &str| {
+            let mut failure = DownloadError::new(
+                &format!("https://libraries.minecraft.net/{}", name),
+                Path::new(name),
+                Some(FileHash::Sha1("a9993e364706816aba3e25717850c26c9cd0d89d")),
+            );
+            failure.attempts = FETCH_ATTEMPTS;
+            failure.reason = "Failed sha1 check".to_string();
+            failure.actual_hash = Some("da39a3ee5e6b4b0d3255bfef95601890afd80709".to_string());
+            Error::Download(Box::new(failure))
+        };
+
+        let values = collect_downloads(vec![Ok(1), Ok(2)]).expect("Failed to collect");
+        assert_eq!(values, vec![1, 2]);
+
+        let result = collect_downloads(vec![
+            Ok(()),
+            Err(failure("a.jar")),
+            Err(Error::Generic("Failed to extract".to_string())),
+            Err(failure("b.jar")),
+        ]);
+        let Err(Error::Downloads(failed)) = &result else {
+            panic!("Expected download errors: {:?}", result);
+        };
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[1].path, Path::new("b.jar"));
+
+        let json = serde_json::to_value(&failed[0]).expect("Failed to serialize");
+        assert_eq!(json["hash_algorithm"], "sha1");
+        assert_eq!(json["attempts"], FETCH_ATTEMPTS);
+        assert_eq!(
+            json["actual_hash"],
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.starts_with("Failed to download 2 files: https://libraries.minecraft.net/a.jar to a.jar failed after 5 attempts: Failed sha1 check (sha1 expected a9993e364706816aba3e25717850c26c9cd0d89d got da39a3ee5e6b4b0d3255bfef95601890afd80709)"));
+
+        // other errors are returned when every download succeeded
+        let result = collect_downloads(vec![Ok(()), Err(Error::NotFound("file".to_string()))]);
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_file_hash() {
+        let path = std::env::temp_dir().join("file_hash_test.txt");
+        tokio::fs::write(&path, b"abc").await.unwrap();
+
+        let sha1 = FileHash::Sha1("a9993e364706816aba3e25717850c26c9cd0d89d");
+        let sha256 =
+            FileHash::Sha256("BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD");
+        let sha512 = FileHash::Sha512(
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        );
+
+        for hash in [sha1, sha256, sha512] {
+            assert!(hash.verify(&path).await.unwrap(), "{:?}", hash);
+        }
+        assert!(!FileHash::Sha1("0000").verify(&path).await.unwrap());
+        assert_eq!(FileHash::strongest([sha1, sha512, sha256]), Some(sha512));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
 }
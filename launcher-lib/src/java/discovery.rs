@@ -0,0 +1,214 @@
+use futures::StreamExt;
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use super::{JAVA_EXECUTABLE, JavaInfo, check_java, major_version};
+use crate::error::{Error, Result};
+
+/// A jre that was found on the system
+#[derive(Debug, Clone, Serialize)]
+pub struct JavaInstall {
+    pub path: PathBuf,
+    pub major_version: usize,
+    #[serde(flatten)]
+    pub info: JavaInfo,
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from)
+}
+
+/// Directories that may contain java homes, with the max depth to search them at.
+fn search_directories() -> Vec<(PathBuf, usize)> {
+    let mut directories = Vec::new();
+
+    if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+        directories.push((PathBuf::from(java_home), 0));
+    }
+
+    if let Some(sdkman) = std::env::var_os("SDKMAN_DIR")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".sdkman")))
+    {
+        directories.push((sdkman.join("candidates").join("java"), 1));
+    }
+
+    if cfg!(windows) {
+        for program_files in ["ProgramFiles", "ProgramFiles(x86)"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .map(PathBuf::from)
+        {
+            for vendor in [
+                "Java",
+                "Eclipse Adoptium",
+                "Eclipse Foundation",
+                "Zulu",
+                "Microsoft",
+                "Amazon Corretto",
+                "BellSoft",
+            ] {
+                directories.push((program_files.join(vendor), 1));
+            }
+        }
+
+        if let Some(appdata) = std::env::var_os("APPDATA").map(PathBuf::from) {
+            directories.push((appdata.join(".minecraft").join("runtime"), 3));
+            directories.push((appdata.join("PrismLauncher").join("java"), 2));
+        }
+        if let Some(local) = std::env::var_os("LOCALAPPDATA").map(PathBuf::from) {
+            // Microsoft Store version of the official launcher
+            directories.push((
+                local
+                    .join("Packages")
+                    .join("Microsoft.4297127D64EC6_8wekyb3d8bbwe")
+                    .join("LocalCache")
+                    .join("Local")
+                    .join("runtime"),
+                3,
+            ));
+        }
+    } else if cfg!(target_os = "macos") {
+        directories.push((PathBuf::from("/Library/Java/JavaVirtualMachines"), 1));
+        if let Some(home) = home_dir() {
+            let support = home.join("Library").join("Application Support");
+            directories.push((home.join("Library/Java/JavaVirtualMachines"), 1));
+            directories.push((support.join("minecraft").join("runtime"), 3));
+            directories.push((support.join("PrismLauncher").join("java"), 2));
+        }
+    } else {
+        directories.push((PathBuf::from("/usr/lib/jvm"), 1));
+        directories.push((PathBuf::from("/usr/lib64/jvm"), 1));
+        directories.push((PathBuf::from("/opt"), 2));
+        if let Some(home) = home_dir() {
+            directories.push((home.join(".minecraft").join("runtime"), 3));
+            directories.push((home.join(".local/share/PrismLauncher/java"), 2));
+        }
+    }
+
+    if let Some(home) = home_dir() {
+        // jdks downloaded by intellij
+        directories.push((home.join(".jdks"), 1));
+    }
+
+    directories
+}
+
+/// Find java executables in a directory and its sub directories.
+fn find_executables(directory: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    for home in [
+        directory.to_path_buf(),
+        directory.join("Contents").join("Home"),
+    ] {
+        let java = home.join("bin").join(JAVA_EXECUTABLE);
+        if java.is_file() {
+            found.push(java);
+            return;
+        }
+    }
+
+    if depth == 0 {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            find_executables(&path, depth - 1, found);
+        }
+    }
+}
+
+/// Get the paths of all java executables that could be found.
+fn find_candidates() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+
+    if let Some(path) = std::env::var_os("PATH") {
+        found.extend(
+            std::env::split_paths(&path)
+                .map(|dir| dir.join(JAVA_EXECUTABLE))
+                .filter(|java| java.is_file()),
+        );
+    }
+
+    for (directory, depth) in search_directories() {
+        find_executables(&directory, depth, &mut found);
+    }
+
+    // symlinks like /usr/bin/java point to a install that is also found in the search directories
+    let mut seen = HashSet::new();
+    found.retain(|java| seen.insert(std::fs::canonicalize(java).unwrap_or_else(|_| java.clone())));
+
+    found
+}
+
+/// Find the java installations on the system, sorted by major version.
+/// Checks `JAVA_HOME`, `PATH`, the common install directories, SDKMAN and the runtimes of other launchers.
+pub async fn discover_java() -> Result<Vec<JavaInstall>> {
+    let candidates = tokio::task::spawn_blocking(find_candidates)
+        .await
+        .map_err(|err| Error::Generic(err.to_string()))?;
+
+    let mut installs = futures::stream::iter(candidates.into_iter().map(|path| async move {
+        match check_java(&path).await {
+            Ok(Some(info)) => major_version(&info.version).map(|major_version| JavaInstall {
+                path,
+                major_version,
+                info,
+            }),
+            Ok(None) => {
+                log::debug!("No java version returned by {}", path.to_string_lossy());
+                None
+            }
+            Err(err) => {
+                log::debug!("Failed to probe {}: {}", path.to_string_lossy(), err);
+                None
+            }
+        }
+    }))
+    .buffer_unordered(8)
+    .filter_map(|install| async move { install })
+    .collect::<Vec<JavaInstall>>()
+    .await;
+
+    installs.sort_by(|a, b| {
+        b.major_version
+            .cmp(&a.major_version)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    Ok(installs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_executables() {
+        let temp = std::env::temp_dir().join("java_discovery_test");
+        let homes = [
+            temp.join("jdk-21"),
+            temp.join("zulu-17.jdk").join("Contents").join("Home"),
+            temp.join("too").join("deep").join("jdk-8"),
+        ];
+        for home in &homes {
+            let bin = home.join("bin");
+            std::fs::create_dir_all(&bin).expect("Failed to create test dir");
+            std::fs::write(bin.join(JAVA_EXECUTABLE), "").expect("Failed to write test file");
+        }
+
+        let mut found = Vec::new();
+        find_executables(&temp, 1, &mut found);
+
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&homes[0].join("bin").join(JAVA_EXECUTABLE)));
+        assert!(found.contains(&homes[1].join("bin").join(JAVA_EXECUTABLE)));
+    }
+}
@@ -0,0 +1,224 @@
+pub mod discovery;
+
+use crate::error::{Error, Result};
+use serde::Serialize;
+use std::{path::Path, time::Duration};
+
+/// javaw is used on windows so no console window is opened
+pub(crate) const JAVA_EXECUTABLE: &str = if cfg!(windows) { "javaw.exe" } else { "java" };
+
+/// Max time a jre has to answer a probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Properties of a jre that are reported by java/JavaInfo.class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JavaInfo {
+    pub version: String,
+    pub vendor: Option<String>,
+    /// `os.arch` of the jre, ex. amd64 or aarch64
+    pub arch: Option<String>,
+    /// 32 or 64 bit, from `sun.arch.data.model`
+    pub data_model: Option<u8>,
+    pub runtime_name: Option<String>,
+}
+
+impl JavaInfo {
+    fn parse(stdout: &str) -> Option<Self> {
+        let mut java_version = None;
+        let mut vendor = None;
+        let mut arch = None;
+        let mut data_model = None;
+        let mut runtime_name = None;
+        for line in stdout.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            match key {
+                "java.version" => java_version = Some(value.to_string()),
+                "java.vendor" => vendor = Some(value.to_string()),
+                "os.arch" => arch = Some(value.to_string()),
+                "sun.arch.data.model" => data_model = value.parse::<u8>().ok(),
+                "java.runtime.name" => runtime_name = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        java_version.map(|version| Self {
+            version,
+            vendor,
+            arch,
+            data_model,
+            runtime_name,
+        })
+    }
+
+    /// Architecture of the jre with the names of `std::env::consts::ARCH`
+    pub fn rust_arch(&self) -> Option<&'static str> {
+        match self.arch.as_deref()? {
+            "amd64" | "x86_64" => Some("x86_64"),
+            "x86" | "i386" | "i486" | "i586" | "i686" => Some("x86"),
+            "aarch64" | "arm64" => Some("aarch64"),
+            "arm" => Some("arm"),
+            _ => None,
+        }
+    }
+
+    /// Check if the jre can run the game on this system.
+    /// Returns the reason if it can not.
+    pub fn incompatibility(&self) -> Option<String> {
+        if self.data_model == Some(32) && cfg!(target_pointer_width = "64") {
+            return Some(format!(
+                "Java {} is a 32-bit runtime, a 64-bit runtime is required",
+                self.version
+            ));
+        }
+
+        let arch = self.rust_arch()?;
+        if arch == std::env::consts::ARCH {
+            return None;
+        }
+
+        if runs_emulated(arch, std::env::consts::ARCH, std::env::consts::OS) {
+            log::warn!(
+                "Java {} is built for {} and will run emulated on this {} system",
+                self.version,
+                arch,
+                std::env::consts::ARCH
+            );
+            return None;
+        }
+
+        Some(format!(
+            "Java {} is built for {}, but this system is {}",
+            self.version,
+            arch,
+            std::env::consts::ARCH
+        ))
+    }
+}
+
+/// Whether the os can run programs built for another architecture,
+/// x86_64 programs run with Rosetta 2 on arm macs and with the x64 emulation of Windows on ARM.
+fn runs_emulated(arch: &str, system_arch: &str, os: &str) -> bool {
+    arch == "x86_64" && system_arch == "aarch64" && matches!(os, "macos" | "windows")
+}
+
+/// Runs the the java program in java/JavaInfo.class
+/// Returns the properties of the jre bin that was pass to this function.
+/// Every probe uses its own temp dir, so jre's can be probed at the same time.
+pub async fn check_java(path: &Path) -> Result<Option<JavaInfo>> {
+    let probe_dir = std::env::temp_dir().join(format!("rmcl-java-probe-{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&probe_dir).await?;
+
+    let info = probe(path, &probe_dir).await;
+
+    if let Err(err) = tokio::fs::remove_dir_all(&probe_dir).await {
+        log::warn!("Failed to remove java probe directory: {}", err);
+    }
+
+    info
+}
+
+async fn probe(path: &Path, probe_dir: &Path) -> Result<Option<JavaInfo>> {
+    let bytes = include_bytes!("../../java/JavaInfo.class");
+    tokio::fs::write(probe_dir.join("JavaInfo.class"), bytes).await?;
+
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        tokio::process::Command::new(path)
+            .arg("-cp")
+            .arg(probe_dir)
+            .arg("JavaInfo")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        Error::Generic(format!(
+            "Java at {} did not respond",
+            path.to_string_lossy()
+        ))
+    })??;
+
+    Ok(JavaInfo::parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Get the major version from a `java.version` property.
+/// Handles the legacy `1.8.0_51` format.
+pub fn major_version(version: &str) -> Option<usize> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    let major = parts.next()?.parse::<usize>().ok()?;
+
+    if major == 1 {
+        return parts.next()?.parse::<usize>().ok();
+    }
+
+    Some(major)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_major_version() {
+        assert_eq!(major_version("1.8.0_51"), Some(8));
+        assert_eq!(major_version("17.0.8"), Some(17));
+        assert_eq!(major_version("21"), Some(21));
+        assert_eq!(major_version("22-ea"), Some(22));
+        assert_eq!(major_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_parse_java_info() {
+        let info = JavaInfo::parse(
+            "os.arch=amd64\njava.version=17.0.15\njava.vendor=Eclipse Adoptium\njava.runtime.name=OpenJDK Runtime Environment\nsun.arch.data.model=64\n",
+        )
+        .expect("Failed to parse java info");
+
+        assert_eq!(info.version, "17.0.15");
+        assert_eq!(info.vendor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(info.rust_arch(), Some("x86_64"));
+        assert_eq!(info.data_model, Some(64));
+        assert_eq!(
+            info.runtime_name.as_deref(),
+            Some("OpenJDK Runtime Environment")
+        );
+
+        assert_eq!(JavaInfo::parse("os.arch=amd64\n"), None);
+    }
+
+    #[test]
+    fn test_incompatibility() {
+        let info = JavaInfo {
+            version: "1.8.0_51".to_string(),
+            vendor: None,
+            arch: Some("x86".to_string()),
+            data_model: Some(32),
+            runtime_name: None,
+        };
+
+        if cfg!(target_pointer_width = "64") {
+            assert!(info.incompatibility().is_some());
+        }
+
+        let arch = if std::env::consts::ARCH == "aarch64" {
+            "aarch64"
+        } else {
+            "amd64"
+        };
+        let info = JavaInfo {
+            arch: Some(arch.to_string()),
+            data_model: Some(64),
+            ..info
+        };
+        if matches!(std::env::consts::ARCH, "x86_64" | "aarch64") {
+            assert_eq!(info.incompatibility(), None);
+        }
+        assert!(runs_emulated("x86_64", "aarch64", "macos"));
+        assert!(runs_emulated("x86_64", "aarch64", "windows"));
+        assert!(!runs_emulated("x86_64", "aarch64", "linux"));
+        assert!(!runs_emulated("aarch64", "x86_64", "windows"));
+    }
+}
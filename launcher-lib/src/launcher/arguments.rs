@@ -23,7 +23,7 @@ pub enum RuleCondition {
     },
 }
 
-const REPLACEMENT_KEYS: [&str; 23] = [
+const REPLACEMENT_KEYS: [&str; 26] = [
     "user_type",
     "clientid",
     "assets_root",
@@ -47,6 +47,9 @@ const REPLACEMENT_KEYS: [&str; 23] = [
     "launcher_version",
     "resolution_width",
     "resolution_height",
+    "user_properties",
+    "game_assets",
+    "auth_session",
 ];
 
 impl RuleCondition {
@@ -120,26 +123,35 @@ impl Arg {
 
         flag
     }
-    fn parse(&self, config: &super::Config) -> Option<String> {
+    fn parse(&self, config: &super::Config) -> Vec<String> {
         match self {
-            Self::Flag(flag) => Some(Self::replace_arg(flag, config)),
+            Self::Flag(flag) => vec![Self::replace_arg(flag, config)],
             Arg::Rule { rules, value } => {
-                if parse_rules(Some(config), rules) {
-                    let flag = match value {
-                        RuleValue::Item(item) => item.to_owned(),
-                        RuleValue::List(items) => items
-                            .iter()
-                            .map(|flag| Arg::replace_arg(flag, config))
-                            .collect::<Vec<String>>()
-                            .join(" "),
-                    };
-                    Some(flag)
-                } else {
-                    None
+                if !parse_rules(Some(config), rules) {
+                    return Vec::new();
+                }
+
+                // each item in a list is its own argument (ex: "--width", "${resolution_width}")
+                match value {
+                    RuleValue::Item(item) => vec![Self::replace_arg(item, config)],
+                    RuleValue::List(items) => items
+                        .iter()
+                        .map(|flag| Arg::replace_arg(flag, config))
+                        .collect::<Vec<String>>(),
                 }
             }
         }
     }
+
+    /// Check if this argument is toggled by the given feature
+    fn has_feature(&self, feature: &str) -> bool {
+        match self {
+            Self::Flag(_) => false,
+            Self::Rule { rules, .. } => rules.iter().any(|rule| {
+                matches!(rule, RuleCondition::Features { features, .. } if features.contains_key(feature))
+            }),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -151,9 +163,50 @@ pub struct Arguments {
 }
 
 impl Arguments {
+    /// Build arguments from a legacy (pre 1.13) `minecraftArguments` string.
+    /// Legacy manifests do not define any jvm arguments so the ones the
+    /// vanilla launcher used for these versions are added.
+    pub fn legacy(minecraft_arguments: &str) -> Self {
+        let os_rule = |name: &str| {
+            vec![RuleCondition::Os {
+                action: "allow".to_string(),
+                os: std::collections::HashMap::from([("name".to_string(), name.to_string())]),
+            }]
+        };
+
+        let jvm = vec![
+            Arg::Rule {
+                rules: os_rule("windows"),
+                value: RuleValue::Item(
+                    "-XX:HeapDumpPath=MojangTricksIntelDriversForPerformance_javaw.exe_minecraft.exe.heapdump"
+                        .to_string(),
+                ),
+            },
+            Arg::Rule {
+                rules: os_rule("osx"),
+                value: RuleValue::Item("-XstartOnFirstThread".to_string()),
+            },
+            Arg::Flag("-Djava.library.path=${natives_directory}".to_string()),
+            Arg::Flag("-cp".to_string()),
+            Arg::Flag("${classpath}".to_string()),
+        ];
+
+        let game = minecraft_arguments
+            .split_whitespace()
+            .map(|flag| Arg::Flag(flag.to_string()))
+            .collect::<Vec<Arg>>();
+
+        Self { game, jvm }
+    }
+
+    /// Check if any game argument is toggled by the given feature
+    pub fn has_game_feature(&self, feature: &str) -> bool {
+        self.game.iter().any(|arg| arg.has_feature(feature))
+    }
+
     pub fn parse_args(list: &[Arg], config: &super::Config) -> Vec<String> {
         list.iter()
-            .filter_map(|arg| arg.parse(config))
+            .flat_map(|arg| arg.parse(config))
             .collect::<Vec<String>>()
     }
 }
@@ -188,4 +241,20 @@ mod tests {
 
         assert!(!parse_rules(None, &rules2))
     }
+
+    #[test]
+    fn test_legacy_arguments() {
+        let arguments = Arguments::legacy(
+            "--username ${auth_player_name}  --version ${version_name} --tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker",
+        );
+
+        assert_eq!(arguments.game.len(), 6);
+        assert!(matches!(&arguments.game[1], Arg::Flag(flag) if flag == "${auth_player_name}"));
+        assert!(
+            arguments
+                .jvm
+                .iter()
+                .any(|arg| matches!(arg, Arg::Flag(flag) if flag == "${classpath}"))
+        );
+    }
 }
@@ -0,0 +1,259 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::Serialize;
+use tokio::fs;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCause {
+    MissingModDependency,
+    WrongJavaVersion,
+    OutOfMemory,
+    MixinFailure,
+    GraphicsDriver,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReport {
+    pub cause: CrashCause,
+    /// Line from the report that best describes the crash
+    pub summary: Option<String>,
+    /// Crash report or hs_err file the report was read from
+    pub path: Option<PathBuf>,
+}
+
+const MISSING_DEPENDENCY: [&str; 5] = [
+    "Missing or unsupported mandatory dependencies",
+    "which is missing!",
+    "Incompatible mods found!",
+    "ModResolutionException",
+    "Mod resolution encountered an incompatible mod set",
+];
+
+const WRONG_JAVA: [&str; 4] = [
+    "UnsupportedClassVersionError",
+    "has been compiled by a more recent version of the Java Runtime",
+    "Unsupported Java version",
+    "Minecraft requires Java",
+];
+
+const MIXIN_FAILURE: [&str; 4] = [
+    "MixinTransformerError",
+    "MixinApplyError",
+    "InvalidInjectionException",
+    "Mixin apply failed",
+];
+
+const OUT_OF_MEMORY: [&str; 3] = [
+    "java.lang.OutOfMemoryError",
+    "There is insufficient memory for the Java Runtime Environment",
+    "Out of memory:",
+];
+
+/// Native modules of common opengl drivers
+const GRAPHICS_DRIVERS: [&str; 14] = [
+    "atio6axx",
+    "atioglxx",
+    "amdvlk",
+    "nvoglv32",
+    "nvoglv64",
+    "libnvidia-glcore",
+    "ig7icd",
+    "ig75icd",
+    "ig8icd",
+    "ig9icd",
+    "igxelpicd",
+    "radeonsi_dri",
+    "iris_dri",
+    "i965_dri",
+];
+
+fn find_line<'a>(content: &'a str, patterns: &[&str]) -> Option<&'a str> {
+    content
+        .lines()
+        .find(|line| patterns.iter().any(|pattern| line.contains(pattern)))
+        .map(str::trim)
+}
+
+/// Get the native frame a jvm crash happened in from a hs_err file
+fn problematic_frame(content: &str) -> Option<&str> {
+    let mut lines = content.lines();
+    lines.find(|line| line.starts_with("# Problematic frame:"))?;
+    lines.next().map(|line| line.trim_start_matches('#').trim())
+}
+
+/// Classify the cause of a crash from the content of a crash report, hs_err file or game log.
+pub fn classify(content: &str) -> (CrashCause, Option<String>) {
+    let checks: [(CrashCause, &[&str]); 4] = [
+        (CrashCause::MissingModDependency, &MISSING_DEPENDENCY),
+        (CrashCause::WrongJavaVersion, &WRONG_JAVA),
+        (CrashCause::MixinFailure, &MIXIN_FAILURE),
+        (CrashCause::OutOfMemory, &OUT_OF_MEMORY),
+    ];
+
+    for (cause, patterns) in checks {
+        if let Some(line) = find_line(content, patterns) {
+            return (cause, Some(line.to_string()));
+        }
+    }
+
+    if let Some(frame) = problematic_frame(content) {
+        let lower = frame.to_lowercase();
+        let cause = if GRAPHICS_DRIVERS.iter().any(|driver| lower.contains(driver)) {
+            CrashCause::GraphicsDriver
+        } else {
+            CrashCause::Unknown
+        };
+        return (cause, Some(frame.to_string()));
+    }
+
+    let summary = content
+        .lines()
+        .find_map(|line| line.strip_prefix("Description: "))
+        .map(|line| line.trim().to_string());
+
+    (CrashCause::Unknown, summary)
+}
+
+/// Get the newest file in a directory that was modified after `since` and matches the filter.
+async fn newest_file<F>(directory: &Path, since: SystemTime, filter: F) -> Result<Option<PathBuf>>
+where
+    F: Fn(&str) -> bool,
+{
+    if !directory.exists() {
+        return Ok(None);
+    }
+
+    let mut newest: Option<(SystemTime, PathBuf)> = None;
+    let mut entries = fs::read_dir(directory).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        if !filter(&name.to_string_lossy()) {
+            continue;
+        }
+
+        let metadata = entry.metadata().await?;
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let modified = metadata.modified()?;
+        if modified < since || newest.as_ref().is_some_and(|(time, _)| *time >= modified) {
+            continue;
+        }
+
+        newest = Some((modified, entry.path()));
+    }
+
+    Ok(newest.map(|(_, path)| path))
+}
+
+/// Find and classify the crash of a game that was started at `since`.
+///
+/// The newest file in `crash-reports` and any `hs_err_pid*.log` file are checked first,
+/// then the output of the process.
+pub async fn analyze_crash(
+    game_directory: &Path,
+    since: SystemTime,
+    output: &[String],
+) -> Result<CrashReport> {
+    let crash_report = newest_file(&game_directory.join("crash-reports"), since, |name| {
+        name.starts_with("crash-") && name.ends_with(".txt")
+    })
+    .await?;
+    let hs_err = newest_file(game_directory, since, |name| {
+        name.starts_with("hs_err_pid") && name.ends_with(".log")
+    })
+    .await?;
+
+    let mut fallback = None;
+    for path in [crash_report, hs_err].into_iter().flatten() {
+        let content = fs::read(&path).await?;
+        let (cause, summary) = classify(&String::from_utf8_lossy(&content));
+
+        if cause != CrashCause::Unknown {
+            return Ok(CrashReport {
+                cause,
+                summary,
+                path: Some(path),
+            });
+        }
+
+        if fallback.is_none() {
+            fallback = Some(CrashReport {
+                cause,
+                summary,
+                path: Some(path),
+            });
+        }
+    }
+
+    // errors like a wrong java version happen before a crash report can be written.
+    let (cause, summary) = classify(&output.join("\n"));
+    if cause != CrashCause::Unknown {
+        return Ok(CrashReport {
+            cause,
+            summary,
+            path: None,
+        });
+    }
+
+    Ok(fallback.unwrap_or(CrashReport {
+        cause,
+        summary,
+        path: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_missing_dependency() {
+        let (cause, summary) = classify(
+            "net.fabricmc.loader.impl.FormattedException: Some of your mods are incompatible with the game or each other!\n\
+             \t - Mod 'Sodium Extra' (sodium-extra) 0.5.1 requires any version of sodium, which is missing!",
+        );
+
+        assert_eq!(cause, CrashCause::MissingModDependency);
+        assert!(summary.is_some_and(|line| line.starts_with("- Mod 'Sodium Extra'")));
+    }
+
+    #[test]
+    fn test_classify_wrong_java() {
+        let (cause, _) = classify(
+            "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0)",
+        );
+
+        assert_eq!(cause, CrashCause::WrongJavaVersion);
+    }
+
+    #[test]
+    fn test_classify_graphics_driver() {
+        let (cause, summary) = classify(
+            "#\n# A fatal error has been detected by the Java Runtime Environment:\n#\n\
+             #  EXCEPTION_ACCESS_VIOLATION (0xc0000005) at pc=0x00007ffb, pid=1234, tid=5678\n\
+             #\n# Problematic frame:\n# C  [atio6axx.dll+0x1a2b3c]\n#",
+        );
+
+        assert_eq!(cause, CrashCause::GraphicsDriver);
+        assert_eq!(summary.as_deref(), Some("C  [atio6axx.dll+0x1a2b3c]"));
+    }
+
+    #[test]
+    fn test_classify_unknown_uses_description() {
+        let (cause, summary) = classify(
+            "---- Minecraft Crash Report ----\n\nTime: 2024-01-01\nDescription: Rendering overlay\n\njava.lang.NullPointerException",
+        );
+
+        assert_eq!(cause, CrashCause::Unknown);
+        assert_eq!(summary.as_deref(), Some("Rendering overlay"));
+    }
+}
@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    database::RwDatabase,
+    error::{Error, Result},
+};
+
+/// Max heap that is suggested, more does not help the game
+const MAX_SUGGESTED_MEMORY: u64 = 12 * 1024;
+/// Lowest max heap the game is started with
+const MIN_MEMORY: u64 = 1024;
+
+/// Garbage collector settings for the jvm
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GcPreset {
+    /// Use the default collector of the jvm
+    #[default]
+    Default,
+    /// G1 tuned for short pauses
+    G1,
+    /// Z garbage collector, needs java 15 or newer
+    Zgc,
+}
+
+impl GcPreset {
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("default") | None => Self::Default,
+            Some("g1") => Self::G1,
+            Some("zgc") => Self::Zgc,
+            Some(value) => {
+                log::warn!("Unknown gc preset '{}', using the jvm default.", value);
+                Self::Default
+            }
+        }
+    }
+
+    pub fn args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Default => &[],
+            Self::G1 => &[
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+UseG1GC",
+                "-XX:G1NewSizePercent=20",
+                "-XX:G1ReservePercent=20",
+                "-XX:MaxGCPauseMillis=50",
+                "-XX:G1HeapRegionSize=32M",
+            ],
+            Self::Zgc => &["-XX:+UseZGC"],
+        }
+    }
+}
+
+/// Memory and jvm settings of a profile
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct JvmSettings {
+    /// Initial heap in MB
+    pub min_memory: Option<u64>,
+    /// Max heap in MB, the suggested heap is used if not set
+    pub max_memory: Option<u64>,
+    pub gc_preset: GcPreset,
+    pub extra_args: Vec<String>,
+}
+
+impl JvmSettings {
+    /// Create the settings from the profile columns.
+    /// Extra args are split with shell word rules, so quoted args with spaces are kept together.
+    pub fn parse(
+        min_memory: Option<i64>,
+        max_memory: Option<i64>,
+        gc_preset: Option<&str>,
+        java_args: Option<&str>,
+    ) -> Result<Self> {
+        let extra_args = match java_args {
+            Some(args) => shlex::split(args).ok_or_else(|| {
+                Error::Generic(format!("Java args have a unclosed quote: {}", args))
+            })?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            min_memory: min_memory.and_then(|value| u64::try_from(value).ok()),
+            max_memory: max_memory.and_then(|value| u64::try_from(value).ok()),
+            gc_preset: GcPreset::from_setting(gc_preset),
+            extra_args,
+        })
+    }
+
+    pub async fn get(profile_id: &str, rwdb: &RwDatabase) -> Result<Self> {
+        let db = rwdb.read().await;
+
+        let profile = sqlx::query!(
+            "SELECT min_memory, max_memory, gc_preset, java_args FROM profiles WHERE id = ?;",
+            profile_id
+        )
+        .fetch_optional(&db.0)
+        .await?
+        .ok_or_else(|| Error::NotFound("Failed to find profile".to_string()))?;
+
+        Self::parse(
+            profile.min_memory,
+            profile.max_memory,
+            profile.gc_preset.as_deref(),
+            profile.java_args.as_deref(),
+        )
+    }
+
+    /// Jvm arguments for the settings
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(min_memory) = self.min_memory {
+            args.push(format!("-Xms{}M", min_memory));
+        }
+        if let Some(max_memory) = self.max_memory {
+            args.push(format!("-Xmx{}M", max_memory));
+        }
+        args.extend(self.gc_preset.args().iter().map(|arg| arg.to_string()));
+        args.extend(self.extra_args.iter().cloned());
+
+        args
+    }
+
+    /// Check that the heap settings are valid and the max heap fits in the system memory.
+    /// A max heap that is more than the free memory only logs a warning, the os can free memory once the game needs it.
+    pub fn validate(&self, total_memory: u64, available_memory: u64) -> Result<()> {
+        if let (Some(min), Some(max)) = (self.min_memory, self.max_memory)
+            && min > max
+        {
+            return Err(Error::Generic(format!(
+                "Min memory of {}MB is more than the max memory of {}MB",
+                min, max
+            )));
+        }
+
+        if let Some(max) = self.max_memory.filter(|max| *max > total_memory) {
+            return Err(Error::Generic(format!(
+                "Max memory of {}MB is more than the {}MB of system memory",
+                max, total_memory
+            )));
+        }
+
+        if let Some(max) = self.max_memory.filter(|max| *max > available_memory) {
+            log::warn!(
+                "Max memory of {}MB is more than the {}MB of free system memory",
+                max,
+                available_memory
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Set the max heap to the suggested one when it was not set.
+    /// It is kept in the free memory, but never below the min heap the user set.
+    pub fn fill_max_memory(&mut self, suggested: u64, available_memory: u64) {
+        if self.max_memory.is_none() {
+            let max = suggested.min(available_memory).max(MIN_MEMORY);
+            self.max_memory = Some(max.max(self.min_memory.unwrap_or_default()));
+        }
+    }
+}
+
+/// Free system memory in MB
+pub fn available_memory() -> u64 {
+    let mut info = sysinfo::System::new();
+    info.refresh_memory();
+    info.available_memory() / (1024 * 1024)
+}
+
+/// Installed system memory in MB
+pub fn total_memory() -> u64 {
+    let mut info = sysinfo::System::new();
+    info.refresh_memory();
+    info.total_memory() / (1024 * 1024)
+}
+
+/// Suggest a max heap in MB for the amount of installed mods.
+/// At least half of the system memory is left for the os and other programs.
+pub fn suggested_max_memory(total_memory_gb: u64, mods: u64) -> u64 {
+    let wanted = if mods == 0 { 2048 } else { 3072 + mods * 24 };
+    let limit = (total_memory_gb * 1024 / 2).max(MIN_MEMORY);
+
+    // round down to 512MB
+    let memory = wanted.min(MAX_SUGGESTED_MEMORY).min(limit) / 512 * 512;
+    memory.max(MIN_MEMORY)
+}
+
+/// Suggest a max heap in MB for a profile from the system memory and its installed mods.
+pub async fn suggest_max_memory(profile_id: &str, rwdb: &RwDatabase) -> Result<u64> {
+    let mods: i64 = {
+        let db = rwdb.read().await;
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM profile_content WHERE profile = ? AND type = 'Mod';",
+            profile_id
+        )
+        .fetch_one(&db.0)
+        .await?
+    };
+
+    Ok(suggested_max_memory(
+        crate::utils::get_ram_gb(),
+        u64::try_from(mods).unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quoted_args() {
+        let settings = JvmSettings::parse(
+            Some(512),
+            Some(4096),
+            Some("g1"),
+            Some(r#"-Dfoo="a b" '-Dbar=c d' -XX:+UseStringDeduplication"#),
+        )
+        .expect("Failed to parse settings");
+
+        assert_eq!(
+            settings.extra_args,
+            vec![
+                "-Dfoo=a b".to_string(),
+                "-Dbar=c d".to_string(),
+                "-XX:+UseStringDeduplication".to_string()
+            ]
+        );
+        assert_eq!(
+            &settings.args()[..3],
+            ["-Xms512M", "-Xmx4096M", "-XX:+UnlockExperimentalVMOptions"]
+        );
+
+        assert!(JvmSettings::parse(None, None, None, Some("-Dfoo=\"a b")).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let settings = JvmSettings {
+            min_memory: Some(4096),
+            max_memory: Some(2048),
+            ..Default::default()
+        };
+        assert!(settings.validate(8192, 8192).is_err());
+
+        let settings = JvmSettings {
+            min_memory: None,
+            max_memory: Some(8192),
+            ..Default::default()
+        };
+        assert!(settings.validate(4096, 4096).is_err());
+        assert!(settings.validate(16384, 16384).is_ok());
+        // more than the free memory is only a warning
+        assert!(settings.validate(16384, 2048).is_ok());
+    }
+
+    #[test]
+    fn test_fill_max_memory() {
+        let mut settings = JvmSettings::default();
+        settings.fill_max_memory(4096, 2048);
+        assert_eq!(settings.max_memory, Some(2048));
+
+        // never below the min heap
+        let mut settings = JvmSettings {
+            min_memory: Some(6144),
+            ..Default::default()
+        };
+        settings.fill_max_memory(4096, 16384);
+        assert_eq!(settings.max_memory, Some(6144));
+        assert!(settings.validate(16384, 16384).is_ok());
+
+        // a max heap that was set is kept
+        let mut settings = JvmSettings {
+            max_memory: Some(3072),
+            ..Default::default()
+        };
+        settings.fill_max_memory(4096, 16384);
+        assert_eq!(settings.max_memory, Some(3072));
+    }
+
+    #[test]
+    fn test_suggested_max_memory() {
+        assert_eq!(suggested_max_memory(16, 0), 2048);
+        assert_eq!(suggested_max_memory(16, 100), 5120);
+        // half of the system memory
+        assert_eq!(suggested_max_memory(8, 200), 4096);
+        assert_eq!(suggested_max_memory(64, 1000), MAX_SUGGESTED_MEMORY);
+        assert_eq!(suggested_max_memory(1, 0), MIN_MEMORY);
+    }
+}
@@ -1,18 +1,28 @@
 pub mod arguments;
+pub mod crash;
+pub mod jvm;
 pub mod logs;
+pub mod ready;
+pub mod verify;
 
 use self::arguments::Arguments;
+use self::jvm::JvmSettings;
+use self::ready::ReadyStrategy;
+use self::verify::ExpectedFile;
 use crate::database::RwDatabase;
+use crate::installer::InstallConfig;
+use crate::java::check_java;
 use crate::manifest::Library;
 
-use crate::models::{profile::Profile, setting::Setting};
-use crate::process::Process;
+use crate::models::{profile::Profile, session::PlaySession, setting::Setting};
+use crate::process::{LaunchStatus, Process};
 use crate::{
     error::{Error, Result},
     manifest::Manifest,
 };
 use normalize_path::NormalizePath;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 #[derive(Debug, Deserialize)]
@@ -23,6 +33,13 @@ pub struct LaunchConfig {
     auth_xuid: String,
 
     profile_id: String,
+
+    /// Name of the singleplayer world to load into
+    quick_play_singleplayer: Option<String>,
+    /// Address of the server to join. ex: `mc.example.com:25565`
+    quick_play_multiplayer: Option<String>,
+    /// Id of the realm to join
+    quick_play_realms: Option<String>,
 }
 
 impl LaunchConfig {
@@ -72,6 +89,11 @@ pub struct Config {
     quick_play_single_player: Option<String>,
     quick_play_realms: Option<String>,
     quick_play_multiplayer: Option<String>,
+
+    // legacy values
+    user_properties: String,
+    game_assets: String,
+    auth_session: String,
 }
 
 impl Config {
@@ -130,17 +152,176 @@ impl Config {
 
             "clientid" => Some(&self.clientid),
             "user_type" => Some(&self.user_type),
+
+            "user_properties" => Some(&self.user_properties),
+            "game_assets" => Some(&self.game_assets),
+            "auth_session" => Some(&self.auth_session),
             _ => None,
         }
     }
 }
 
+/// The resolved command line of a game launch
+#[derive(Debug)]
+pub struct LaunchCommand {
+    pub profile_id: String,
+    pub java_exe: String,
+    pub game_directory: PathBuf,
+    pub natives_directory: PathBuf,
+    pub quick_play_directory: Option<PathBuf>,
+    pub classpath: Vec<String>,
+    /// jvm arguments including the profile java args and logging config
+    pub jvm_args: Vec<String>,
+    pub main_class: String,
+    pub game_args: Vec<String>,
+    /// memory and jvm settings of the profile, with the max memory resolved
+    pub jvm: JvmSettings,
+    pub ready_strategy: ReadyStrategy,
+    pub max_wait_time: Option<u64>,
+    /// files that are checked before the game is started
+    pub files: Vec<ExpectedFile>,
+    /// install config used to repair the profile
+    install_config: InstallConfig,
+    access_token: String,
+}
+
+impl LaunchCommand {
+    /// Arguments passed to the java executable
+    pub fn args(&self) -> Vec<String> {
+        let mut args = self.jvm_args.clone();
+        args.push(self.main_class.clone());
+        args.extend(self.game_args.iter().cloned());
+        args
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClasspathEntry {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Launch command that is safe to share, the access token is redacted.
+#[derive(Debug, Serialize)]
+pub struct LaunchDryRun {
+    pub java_exe: String,
+    pub java_exists: bool,
+    pub working_directory: String,
+    pub classpath: Vec<ClasspathEntry>,
+    pub jvm_args: Vec<String>,
+    pub main_class: String,
+    pub game_args: Vec<String>,
+}
+
+const REDACTED: &str = "<redacted>";
+
+fn redact(args: &[String], secret: &str) -> Vec<String> {
+    if secret.is_empty() {
+        return args.to_vec();
+    }
+
+    args.iter()
+        .map(|arg| arg.replace(secret, REDACTED))
+        .collect::<Vec<String>>()
+}
+
+/// Resolve the launch command of a profile without starting the game.
+pub async fn dry_run(db: &RwDatabase, launch_config: LaunchConfig) -> Result<LaunchDryRun> {
+    let command = prepare_launch(db, launch_config).await?;
+
+    let classpath = command
+        .classpath
+        .iter()
+        .map(|path| ClasspathEntry {
+            exists: Path::new(path).is_file(),
+            path: path.to_owned(),
+        })
+        .collect::<Vec<ClasspathEntry>>();
+
+    Ok(LaunchDryRun {
+        java_exists: Path::new(&command.java_exe).is_file(),
+        java_exe: command.java_exe,
+        working_directory: command.game_directory.to_string_lossy().to_string(),
+        classpath,
+        jvm_args: redact(&command.jvm_args, &command.access_token),
+        main_class: command.main_class,
+        game_args: redact(&command.game_args, &command.access_token),
+    })
+}
+
 pub async fn start_game(
     db: &RwDatabase,
     processes: &tokio::sync::RwLock<crate::process::Processes>,
     launch_config: LaunchConfig,
-    on_ready: tokio::sync::oneshot::Sender<String>,
+    on_ready: tokio::sync::oneshot::Sender<LaunchStatus>,
 ) -> Result<()> {
+    let command = prepare_launch(db, launch_config).await?;
+
+    // refuse a jre that can not run the game, instead of failing with a jvm error
+    let java = check_java(Path::new(&command.java_exe))
+        .await?
+        .ok_or_else(|| Error::Generic(format!("Failed to check java at {}", command.java_exe)))?;
+    if let Some(reason) = java.incompatibility() {
+        return Err(Error::Generic(reason));
+    }
+
+    command
+        .jvm
+        .validate(jvm::total_memory(), jvm::available_memory())?;
+
+    let broken = verify::verify_files(&command.files).await?;
+    if !broken.is_empty() {
+        log::error!("Game files are missing or corrupt: {:?}", broken);
+
+        if Setting::get("option.repair_on_launch", db)
+            .await?
+            .is_some_and(|setting| setting.value == "TRUE")
+        {
+            verify::enqueue_repair(&command.profile_id, &command.install_config, db).await?;
+        }
+
+        return Err(Error::BrokenFiles(broken));
+    }
+
+    for directory in [
+        Some(&command.game_directory),
+        Some(&command.natives_directory),
+        command.quick_play_directory.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if !directory.exists() {
+            fs::create_dir_all(directory).await?;
+        }
+    }
+
+    let args = command.args();
+
+    log::debug!("Spawning processes");
+    let ps = Process::spawn(
+        command.java_exe,
+        args,
+        command.profile_id,
+        &command.game_directory,
+        on_ready,
+        command.max_wait_time,
+        command.ready_strategy,
+    )
+    .await?;
+
+    if let Err(err) = PlaySession::start(&ps.profile_id, &ps.uuid, db).await {
+        log::error!("Failed to start play session: {}", err);
+    }
+
+    let mut state = processes.write().await;
+    state.insert(ps);
+
+    Ok(())
+}
+
+/// Resolve everything that is needed to launch a profile.
+async fn prepare_launch(db: &RwDatabase, launch_config: LaunchConfig) -> Result<LaunchCommand> {
     let root_directory = Setting::path("path.app", db)
         .await?
         .ok_or_else(|| Error::NotFound("No application path was set!".to_string()))?;
@@ -155,12 +336,20 @@ pub async fn start_game(
             ))
         })?;
 
+    let mut jvm_settings = JvmSettings::get(&profile.id, db).await?;
+    if jvm_settings.max_memory.is_none() {
+        let suggested = jvm::suggest_max_memory(&profile.id, db).await?;
+        jvm_settings.fill_max_memory(suggested, jvm::available_memory());
+    }
+
     let version_id = profile.version_id()?;
+    let install_config = InstallConfig::new(
+        profile.version.clone(),
+        profile.loader,
+        profile.loader_version.clone(),
+    );
 
     let game_directory = root_directory.join("profiles").join(&profile.id);
-    if !game_directory.exists() {
-        fs::create_dir_all(&game_directory).await?;
-    }
 
     let lib_direcotry = runtime_directory.join("libraries");
 
@@ -172,15 +361,6 @@ pub async fn start_game(
         )));
     }
 
-    let natives_directory = runtime_directory
-        .join("natives")
-        .join(&version_id)
-        .normalize();
-
-    if !natives_directory.exists() || !natives_directory.is_dir() {
-        fs::create_dir_all(&natives_directory).await?;
-    }
-
     let manifest_directory = runtime_directory
         .join("versions")
         .join(&version_id)
@@ -194,7 +374,79 @@ pub async fn start_game(
     }
 
     let manifest = Manifest::read_manifest(&manifest_directory, true).await?;
+
+    let ready_detection = Setting::get("option.ready_detection", db).await?;
+    let ready_strategy = ReadyStrategy::from_setting(
+        ready_detection
+            .as_ref()
+            .map(|setting| setting.value.as_str()),
+        profile.loader,
+        &manifest,
+    );
+
     let classpath = manifest.libs_as_string(&runtime_directory, &version_id)?;
+    let files = verify::expected_files(&runtime_directory, &version_id, &manifest)?;
+
+    // natives are extracted when the vanilla version is installed so modded
+    // versions need to use the natives of the version they inherit from.
+    let natives_directory = runtime_directory
+        .join("natives")
+        .join(manifest.inherits_from.as_ref().unwrap_or(&version_id))
+        .normalize();
+
+    let arguments = manifest.launch_arguments();
+
+    // Quick play was added in 1.20, older versions can only join a server by using --server/--port
+    let has_quick_play = arguments.has_game_feature("has_quick_plays_support");
+    let has_quick_play_target = launch_config.quick_play_singleplayer.is_some()
+        || launch_config.quick_play_multiplayer.is_some()
+        || launch_config.quick_play_realms.is_some();
+
+    let quick_play_directory =
+        (has_quick_play && has_quick_play_target).then(|| game_directory.join("quickPlay"));
+
+    let (quick_play_path, legacy_server) = match (has_quick_play, has_quick_play_target) {
+        (true, true) => (
+            quick_play_directory
+                .as_ref()
+                .map(|directory| directory.join("log.json").to_string_lossy().to_string()),
+            None,
+        ),
+        (false, true) => {
+            if launch_config.quick_play_singleplayer.is_some()
+                || launch_config.quick_play_realms.is_some()
+            {
+                log::warn!(
+                    "Version {} does not support quick play for worlds or realms.",
+                    version_id
+                );
+            }
+            (
+                None,
+                launch_config
+                    .quick_play_multiplayer
+                    .as_deref()
+                    .map(split_server_address),
+            )
+        }
+        _ => (None, None),
+    };
+
+    let (quick_play_single_player, quick_play_multiplayer, quick_play_realms) = if has_quick_play {
+        (
+            launch_config.quick_play_singleplayer,
+            launch_config.quick_play_multiplayer,
+            launch_config.quick_play_realms,
+        )
+    } else {
+        (None, None, None)
+    };
+
+    let auth_session = format!(
+        "token:{}:{}",
+        launch_config.auth_access_token, launch_config.auth_uuid
+    );
+    let access_token = launch_config.auth_access_token.clone();
 
     let config = Config {
         auth_player_name: launch_config.auth_player_name,
@@ -215,25 +467,27 @@ pub async fn start_game(
             .to_owned(),
         resolution_height: profile.resolution_height,
         resolution_width: profile.resolution_width,
-        classpath,
+        classpath: classpath.clone(),
         clientid: "c4502edb-87c6-40cb-b595-64a280cf8906".to_string(),
         user_type: "msa".to_string(),
         assets_root: assets_root.to_string_lossy().to_string(),
         game_directory: game_directory.to_string_lossy().to_string(),
-        additonal_java_arguments: profile.java_args.map(|args| {
-            args.split_whitespace()
-                .map(|x| x.to_owned())
-                .collect::<Vec<String>>()
-        }),
+        additonal_java_arguments: Some(jvm_settings.args()),
         natives_directory: natives_directory.to_string_lossy().to_string(),
-        quick_play_multiplayer: None,
-        quick_play_path: None,
-        quick_play_realms: None,
-        quick_play_single_player: None,
+        quick_play_multiplayer,
+        quick_play_path,
+        quick_play_realms,
+        quick_play_single_player,
+        user_properties: "{}".to_string(),
+        game_assets: assets_root.to_string_lossy().to_string(),
+        auth_session,
     };
 
-    let game_args = Arguments::parse_args(&manifest.arguments.game, &config);
-    let jvm_args = Arguments::parse_args(&manifest.arguments.jvm, &config);
+    let mut game_args = Arguments::parse_args(&arguments.game, &config);
+    if let Some((host, port)) = legacy_server {
+        game_args.extend(["--server".to_string(), host, "--port".to_string(), port]);
+    }
+    let jvm_args = Arguments::parse_args(&arguments.jvm, &config);
 
     // exec_path + jvmArgs+ (client jvm args) + logging + mainClass + gameFlags + ?(extraFlags)
     let java_version = manifest
@@ -252,24 +506,113 @@ pub async fn start_game(
         args.extend(additonal_java_arguments.to_owned());
     }
 
-    args.push(manifest.main_class);
-    args.extend(game_args);
+    if let Some(logging) = manifest.logging.as_ref().and_then(|x| x.client.as_ref()) {
+        let config_id = logging.file.id.as_ref().ok_or(Error::NotFound(
+            "Failed to get logging config id".to_string(),
+        ))?;
+        let config_path = assets_root.join("log_configs").join(config_id);
+
+        if config_path.is_file() {
+            args.push(
+                logging
+                    .argument
+                    .replace("${path}", &config_path.to_string_lossy()),
+            );
+        } else {
+            log::warn!(
+                "Logging config was not found at ({}), launching without it.",
+                config_path.to_string_lossy()
+            );
+        }
+    }
 
     let max_wait_time = Setting::get_as_u64("option.max_launch_wait", db).await?;
 
-    log::debug!("Spawning processes");
-    let ps = Process::spawn(
+    Ok(LaunchCommand {
+        profile_id: profile.id,
         java_exe,
-        args,
-        profile.id,
-        &game_directory,
-        on_ready,
+        game_directory,
+        natives_directory,
+        quick_play_directory,
+        classpath: classpath
+            .split(Library::get_class_sep())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.to_string())
+            .collect::<Vec<String>>(),
+        jvm_args: args,
+        main_class: manifest.main_class,
+        game_args,
+        jvm: jvm_settings,
+        ready_strategy,
         max_wait_time,
-    )
-    .await?;
+        files,
+        install_config,
+        access_token,
+    })
+}
 
-    let mut state = processes.write().await;
-    state.insert(ps);
+/// Split a server address into its host and port.
+/// The port defaults to 25565 when not given.
+fn split_server_address(address: &str) -> (String, String) {
+    let address = address.trim();
+
+    // ipv6 addresses are wrapped in [] when a port is given. ex: [::1]:25565
+    if let Some((host, port)) = address
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        let port = port.strip_prefix(':').unwrap_or_default();
+        return (
+            host.to_string(),
+            if port.is_empty() { "25565" } else { port }.to_string(),
+        );
+    }
 
-    Ok(())
+    match address.rsplit_once(':') {
+        Some((host, port))
+            if !host.contains(':') && !port.is_empty() && port.parse::<u16>().is_ok() =>
+        {
+            (host.to_string(), port.to_string())
+        }
+        _ => (address.to_string(), "25565".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{REDACTED, redact, split_server_address};
+
+    #[test]
+    fn test_split_server_address() {
+        assert_eq!(
+            split_server_address("mc.example.com"),
+            ("mc.example.com".to_string(), "25565".to_string())
+        );
+        assert_eq!(
+            split_server_address("mc.example.com:25570"),
+            ("mc.example.com".to_string(), "25570".to_string())
+        );
+        assert_eq!(
+            split_server_address("[::1]:25570"),
+            ("::1".to_string(), "25570".to_string())
+        );
+        assert_eq!(
+            split_server_address("::1"),
+            ("::1".to_string(), "25565".to_string())
+        );
+    }
+    #[test]
+    fn test_redact() {
+        let args = vec![
+            "--accessToken".to_string(),
+            "secret-token".to_string(),
+            "--session".to_string(),
+            "token:secret-token:uuid".to_string(),
+        ];
+
+        let redacted = redact(&args, "secret-token");
+        assert_eq!(redacted[1], REDACTED);
+        assert_eq!(redacted[3], format!("token:{}:uuid", REDACTED));
+        assert_eq!(redact(&args, ""), args);
+    }
 }
@@ -0,0 +1,284 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::{
+    error::Result, launcher::logs, manifest::Manifest, models::profile::Loader, process::OutputLine,
+};
+
+/// How long the game output has to be quiet before the game is considered started.
+const ACTIVITY_SETTLE_TIME: Duration = Duration::from_secs(3);
+
+/// How the launcher decides that the game has finished starting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "patterns", rename_all = "snake_case")]
+pub enum ReadyStrategy {
+    /// Wait for any of the patterns to be written to the log or the process output
+    LogPattern(Vec<String>),
+    /// Wait for the game window to be visible. Only supported on windows.
+    Window,
+    /// Wait for the game to write output and then go quiet
+    LogActivity,
+    /// Do not wait for the game
+    None,
+}
+
+/// The signal that marked the game as started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ReadySignal {
+    /// The pattern that was matched
+    LogPattern(String),
+    Window,
+    LogActivity,
+    None,
+}
+
+impl ReadyStrategy {
+    /// Pick the strategy for a version and loader.
+    pub fn detect(loader: Loader, manifest: &Manifest) -> Self {
+        // versions before 1.7 have no log4j config so they only write to stdout
+        // and use log messages that differ between releases.
+        if manifest.logging.is_none() {
+            return Self::LogActivity;
+        }
+
+        Self::log_pattern(loader)
+    }
+
+    /// Log pattern strategy with the default patterns for a loader
+    pub fn log_pattern(loader: Loader) -> Self {
+        let mut patterns = vec!["Sound engine started".to_string()];
+        if loader == Loader::Forge {
+            patterns.push("Forge Mod Loader has successfully loaded".to_string());
+        }
+
+        Self::LogPattern(patterns)
+    }
+
+    /// Get the strategy from the value of the `option.ready_detection` setting.
+    /// Unset, `auto` or unsupported values fallback to [`ReadyStrategy::detect`].
+    pub fn from_setting(value: Option<&str>, loader: Loader, manifest: &Manifest) -> Self {
+        let strategy = match value {
+            Some("auto") | None => None,
+            Some("log_pattern") => Some(Self::log_pattern(loader)),
+            Some("window") => Some(Self::Window),
+            Some("log_activity") => Some(Self::LogActivity),
+            Some("none") => Some(Self::None),
+            Some(value) => {
+                log::warn!("Unknown ready detection '{}', using auto.", value);
+                None
+            }
+        };
+
+        match strategy {
+            Some(Self::Window) if !cfg!(windows) => {
+                log::warn!("Window ready detection is not supported on this platform.");
+                Self::detect(loader, manifest)
+            }
+            Some(strategy) => strategy,
+            None => Self::detect(loader, manifest),
+        }
+    }
+}
+
+/// Polls a started game for its ready signal.
+pub struct ReadyWatcher {
+    strategy: ReadyStrategy,
+    pid: u32,
+    log_file: PathBuf,
+    started: SystemTime,
+    output: Option<broadcast::Receiver<OutputLine>>,
+    log_file_ready: bool,
+    cursor: u64,
+    last_activity: Option<SystemTime>,
+}
+
+impl ReadyWatcher {
+    pub fn new(
+        strategy: ReadyStrategy,
+        pid: u32,
+        log_file: PathBuf,
+        started: SystemTime,
+        output: Option<broadcast::Receiver<OutputLine>>,
+    ) -> Self {
+        Self {
+            strategy,
+            pid,
+            log_file,
+            started,
+            output,
+            log_file_ready: false,
+            cursor: 0,
+            last_activity: None,
+        }
+    }
+
+    /// Get the new lines from the process output and the latest log
+    async fn read_new_lines(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+
+        if let Some(output) = &mut self.output {
+            loop {
+                match output.try_recv() {
+                    Ok(item) => lines.push(item.line),
+                    Err(TryRecvError::Lagged(_)) => continue,
+                    Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+                }
+            }
+        }
+
+        if !self.log_file_ready {
+            // skip the log file of the last time the game was run
+            self.log_file_ready =
+                self.log_file.exists() && self.log_file.metadata()?.modified()? > self.started;
+        }
+
+        if self.log_file_ready {
+            let result = logs::get_latest_log_cursor(&self.log_file, self.cursor).await?;
+            self.cursor = result.cursor;
+            lines.extend(
+                result
+                    .line
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(|line| line.to_string()),
+            );
+        }
+
+        Ok(lines)
+    }
+
+    /// Check if the game is ready, returns the signal that fired if it is.
+    pub async fn poll(&mut self) -> Result<Option<ReadySignal>> {
+        match self.strategy {
+            ReadyStrategy::None => return Ok(Some(ReadySignal::None)),
+            ReadyStrategy::Window => {
+                return Ok(has_visible_window(self.pid).then_some(ReadySignal::Window));
+            }
+            _ => {}
+        }
+
+        let lines = self.read_new_lines().await?;
+
+        if let ReadyStrategy::LogPattern(patterns) = &self.strategy {
+            let signal = lines.iter().find_map(|line| {
+                patterns
+                    .iter()
+                    .find(|pattern| line.contains(pattern.as_str()))
+                    .map(|pattern| ReadySignal::LogPattern(pattern.clone()))
+            });
+            return Ok(signal);
+        }
+
+        let now = SystemTime::now();
+        if !lines.is_empty() {
+            self.last_activity = Some(now);
+            return Ok(None);
+        }
+
+        let settled = self.last_activity.is_some_and(|last| {
+            now.duration_since(last)
+                .is_ok_and(|quiet| quiet >= ACTIVITY_SETTLE_TIME)
+        });
+
+        Ok(settled.then_some(ReadySignal::LogActivity))
+    }
+}
+
+#[cfg(windows)]
+fn has_visible_window(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, IsWindowVisible,
+    };
+    use windows_sys::core::BOOL;
+
+    struct Search {
+        pid: u32,
+        found: bool,
+    }
+
+    unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = unsafe { &mut *(lparam as *mut Search) };
+        let mut window_pid = 0u32;
+        unsafe { GetWindowThreadProcessId(hwnd, &mut window_pid) };
+
+        if window_pid == search.pid && unsafe { IsWindowVisible(hwnd) } != 0 {
+            search.found = true;
+            // stop enumerating
+            return 0;
+        }
+
+        1
+    }
+
+    let mut search = Search { pid, found: false };
+    unsafe { EnumWindows(Some(callback), &mut search as *mut Search as LPARAM) };
+
+    search.found
+}
+
+#[cfg(not(windows))]
+fn has_visible_window(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_legacy_version() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{
+                "id": "1.5.2",
+                "mainClass": "net.minecraft.client.Minecraft",
+                "minecraftArguments": "${auth_player_name}",
+                "libraries": [],
+                "releaseTime": "2013-04-25T15:45:00+00:00",
+                "time": "2013-04-25T15:45:00+00:00",
+                "type": "release"
+            }"#,
+        )
+        .expect("Failed to parse manifest");
+
+        assert_eq!(
+            ReadyStrategy::detect(Loader::Vanilla, &manifest),
+            ReadyStrategy::LogActivity
+        );
+        assert_eq!(
+            ReadyStrategy::from_setting(Some("none"), Loader::Vanilla, &manifest),
+            ReadyStrategy::None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_pattern_signal() {
+        let started = SystemTime::now() - Duration::from_secs(5);
+        let log_file = std::env::temp_dir().join("ready_test_latest.log");
+        std::fs::write(
+            &log_file,
+            "[12:00:00] [Render thread/INFO]: Setting user: Dev\n[12:00:05] [Render thread/INFO]: Sound engine started\n",
+        )
+        .expect("Failed to write test file!");
+
+        let mut watcher = ReadyWatcher::new(
+            ReadyStrategy::LogPattern(vec!["Sound engine started".to_string()]),
+            0,
+            log_file,
+            started,
+            None,
+        );
+
+        let signal = watcher.poll().await.expect("Failed to poll");
+        assert_eq!(
+            signal,
+            Some(ReadySignal::LogPattern("Sound engine started".to_string()))
+        );
+    }
+}
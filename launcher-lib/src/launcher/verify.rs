@@ -0,0 +1,234 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use futures::StreamExt;
+use normalize_path::NormalizePath;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    database::RwDatabase,
+    error::{Error, Result},
+    installer::{InstallConfig, utils::FileHash},
+    manifest::Manifest,
+    utils::current_timestamp,
+};
+
+/// A file the game needs to start
+#[derive(Debug, Clone)]
+pub struct ExpectedFile {
+    pub path: PathBuf,
+    /// Files without a sha1 are only checked for existence
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrokenReason {
+    Missing,
+    HashMismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenFile {
+    pub path: PathBuf,
+    pub reason: BrokenReason,
+}
+
+impl Display for BrokenFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.reason {
+            BrokenReason::Missing => "missing",
+            BrokenReason::HashMismatch => "corrupt",
+        };
+        write!(f, "{} ({})", self.path.to_string_lossy(), reason)
+    }
+}
+
+/// Get the libraries, client jar and asset index that a version needs to start.
+pub fn expected_files(
+    runtime_directory: &Path,
+    version_id: &str,
+    manifest: &Manifest,
+) -> Result<Vec<ExpectedFile>> {
+    let libraries_directory = runtime_directory.join("libraries");
+
+    let mut files = Vec::new();
+    for lib in &manifest.libraries {
+        if let Some(path) = lib.get_lib(&libraries_directory)? {
+            files.push(ExpectedFile {
+                path: PathBuf::from(path),
+                sha1: lib.artifact_sha1().map(|sha1| sha1.to_string()),
+            });
+        }
+    }
+
+    let client_id = manifest.jar.as_deref().unwrap_or(version_id);
+    let client_sha1 = manifest
+        .downloads
+        .as_ref()
+        .map(|downloads| downloads.client.sha1.clone());
+    let vanilla_id = manifest.inherits_from.as_deref().unwrap_or(client_id);
+
+    // modded versions use a copy of the vanilla jar, only the vanilla jar can be checked against the manifest.
+    files.push(ExpectedFile {
+        path: runtime_directory
+            .join(format!("versions/{0}/{0}.jar", vanilla_id))
+            .normalize(),
+        sha1: client_sha1,
+    });
+    if client_id != vanilla_id {
+        files.push(ExpectedFile {
+            path: runtime_directory
+                .join(format!("versions/{0}/{0}.jar", client_id))
+                .normalize(),
+            sha1: None,
+        });
+    }
+
+    if let Some(asset_index) = &manifest.asset_index {
+        let id = asset_index
+            .id
+            .as_ref()
+            .or(manifest.assets.as_ref())
+            .ok_or_else(|| Error::NotFound("Failed to get assets version".to_string()))?;
+
+        files.push(ExpectedFile {
+            path: runtime_directory
+                .join(format!("assets/indexes/{}.json", id))
+                .normalize(),
+            sha1: Some(asset_index.sha1.clone()),
+        });
+    }
+
+    Ok(files)
+}
+
+async fn verify_file(file: ExpectedFile) -> Result<Option<BrokenFile>> {
+    if !file.path.is_file() {
+        return Ok(Some(BrokenFile {
+            path: file.path,
+            reason: BrokenReason::Missing,
+        }));
+    }
+
+    let Some(sha1) = file.sha1 else {
+        return Ok(None);
+    };
+
+    if !FileHash::Sha1(&sha1).verify(&file.path).await? {
+        return Ok(Some(BrokenFile {
+            path: file.path,
+            reason: BrokenReason::HashMismatch,
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Check that all files exist and match there sha1 hash.
+/// Returns the files that are missing or corrupt.
+pub async fn verify_files(files: &[ExpectedFile]) -> Result<Vec<BrokenFile>> {
+    let results = futures::stream::iter(files.iter().cloned().map(verify_file))
+        .buffer_unordered(16)
+        .collect::<Vec<Result<Option<BrokenFile>>>>()
+        .await;
+
+    let mut broken = Vec::new();
+    for result in results {
+        if let Some(file) = result? {
+            broken.push(file);
+        }
+    }
+
+    Ok(broken)
+}
+
+/// Queue a reinstall of a profile's client. Files that are valid are not downloaded again.
+/// Does nothing if the client is already queued for the profile.
+pub async fn enqueue_repair(
+    profile_id: &str,
+    config: &InstallConfig,
+    rwdb: &RwDatabase,
+) -> Result<()> {
+    let db = rwdb.write().await;
+
+    let queued = sqlx::query(
+        "SELECT id FROM download_queue WHERE profile_id = ? AND content_type = 'Client' AND state IN ('PENDING','CURRENT');",
+    )
+    .bind(profile_id)
+    .fetch_optional(&db.0)
+    .await?;
+
+    if queued.is_some() {
+        return Ok(());
+    }
+
+    let queue_id = Uuid::new_v4().to_string();
+    let metadata = serde_json::to_string(config)?;
+    let timestamp = current_timestamp()?;
+
+    sqlx::query("INSERT INTO download_queue ('id','display','priority','display_name','profile_id','created','content_type','metadata','state') VALUES (?,?,?,?,?,?,?,?,'PENDING')")
+        .bind(queue_id)
+        .bind(true)
+        .bind(1)
+        .bind(format!("Repair Minecraft {}", config.version()))
+        .bind(profile_id)
+        .bind(timestamp)
+        .bind("Client")
+        .bind(metadata)
+        .execute(&db.0)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_files() {
+        let temp = std::env::temp_dir().join("verify_test");
+        std::fs::create_dir_all(&temp).expect("Failed to create test dir");
+
+        let valid = temp.join("valid.txt");
+        let corrupt = temp.join("corrupt.txt");
+        std::fs::write(&valid, "test").expect("Failed to write test file!");
+        std::fs::write(&corrupt, "corrupt").expect("Failed to write test file!");
+
+        // sha1 of "test"
+        let sha1 = "a94a8fe5ccb19ba61c4c0873d391e987982fbbd3".to_string();
+
+        let broken = verify_files(&[
+            ExpectedFile {
+                path: valid,
+                sha1: Some(sha1.clone()),
+            },
+            ExpectedFile {
+                path: corrupt.clone(),
+                sha1: Some(sha1),
+            },
+            ExpectedFile {
+                path: temp.join("missing.txt"),
+                sha1: None,
+            },
+        ])
+        .await
+        .expect("Failed to verify files");
+
+        assert_eq!(broken.len(), 2);
+        assert!(
+            broken
+                .iter()
+                .any(|file| file.path == corrupt && file.reason == BrokenReason::HashMismatch)
+        );
+        assert!(
+            broken
+                .iter()
+                .any(|file| file.reason == BrokenReason::Missing)
+        );
+    }
+}
@@ -5,9 +5,10 @@ pub mod database;
 pub mod error;
 pub mod events;
 pub mod installer;
-mod java;
+pub mod java;
 pub mod launcher;
 mod manifest;
 pub mod models;
+pub mod offline;
 pub mod process;
 pub mod utils;
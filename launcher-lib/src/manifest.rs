@@ -33,12 +33,17 @@ pub mod asset_index {
 pub struct Manifest {
     #[serde(alias = "inheritsFrom")]
     pub inherits_from: Option<String>,
+    #[serde(default)]
     pub arguments: Arguments,
+    /// Single argument string used by versions older then 1.13 in place of `arguments`
+    pub minecraft_arguments: Option<String>,
     pub asset_index: Option<File>,
     pub assets: Option<String>,
     pub compliance_level: Option<usize>,
     pub downloads: Option<Downloads>,
     pub id: String,
+    /// Version id of the client jar to use. Set by legacy forge manifests
+    pub jar: Option<String>,
     pub java_version: Option<JavaVersion>,
     pub libraries: Vec<Library>,
     pub logging: Option<Logging>,
@@ -56,29 +61,27 @@ impl Manifest {
         let manifest_raw = tokio::fs::read_to_string(&manifest_dir).await?;
         let mut manifest = serde_json::from_str::<Manifest>(&manifest_raw)?;
 
-        if do_inhert {
-            if let Some(inherts) = &manifest.inherits_from {
-                log::debug!("Inherting manifest from {}", inherts);
-                let root_dir = manifest_dir
-                    .parent()
-                    .ok_or_else(|| Error::Generic("Failed to get parent dir".into()))?
-                    .parent()
-                    .ok_or_else(|| Error::Generic("Failed to get parent dir".into()))?;
-
-                if !root_dir.exists() {
-                    return Err(Error::NotFound(
-                        "Failed to find parent manifest".to_string(),
-                    ));
-                }
+        if do_inhert && let Some(inherts) = &manifest.inherits_from {
+            log::debug!("Inherting manifest from {}", inherts);
+            let root_dir = manifest_dir
+                .parent()
+                .ok_or_else(|| Error::Generic("Failed to get parent dir".into()))?
+                .parent()
+                .ok_or_else(|| Error::Generic("Failed to get parent dir".into()))?;
+
+            if !root_dir.exists() {
+                return Err(Error::NotFound(
+                    "Failed to find parent manifest".to_string(),
+                ));
+            }
 
-                // Load base manifest
-                let path = root_dir.join(format!("{0}/{0}.json", inherts));
+            // Load base manifest
+            let path = root_dir.join(format!("{0}/{0}.json", inherts));
 
-                let raw = tokio::fs::read_to_string(&path).await?;
-                let base_manifest = serde_json::from_str::<Manifest>(&raw)?;
+            let raw = tokio::fs::read_to_string(&path).await?;
+            let base_manifest = serde_json::from_str::<Manifest>(&raw)?;
 
-                manifest = base_manifest.inherit(manifest)?;
-            }
+            manifest = base_manifest.inherit(manifest)?;
         }
 
         Ok(manifest)
@@ -96,6 +99,25 @@ impl Manifest {
         self.arguments.game.extend(manifest.arguments.game);
         self.arguments.jvm.extend(manifest.arguments.jvm);
 
+        // legacy manifests repeat the full argument string, so the child's replaces the parent's.
+        if manifest.minecraft_arguments.is_some() {
+            self.minecraft_arguments = manifest.minecraft_arguments;
+        }
+        if manifest.jar.is_some() {
+            self.jar = manifest.jar;
+        }
+        if manifest.asset_index.is_some() {
+            self.asset_index = manifest.asset_index;
+            self.assets = manifest.assets;
+        }
+        if manifest
+            .logging
+            .as_ref()
+            .is_some_and(|logging| logging.client.is_some())
+        {
+            self.logging = manifest.logging;
+        }
+
         self.libraries.extend(manifest.libraries);
 
         let mut seen = HashMap::<String, Library>::new();
@@ -124,6 +146,21 @@ impl Manifest {
         Ok(self)
     }
 
+    /// Get the game and jvm arguments for this manifest.
+    /// Legacy manifests have there argument string split into flags and the jvm arguments that
+    /// newer manifests define are added.
+    pub fn launch_arguments(&self) -> Arguments {
+        match &self.minecraft_arguments {
+            Some(minecraft_arguments) => {
+                let mut arguments = Arguments::legacy(minecraft_arguments);
+                arguments.game.extend(self.arguments.game.iter().cloned());
+                arguments.jvm.extend(self.arguments.jvm.iter().cloned());
+                arguments
+            }
+            None => self.arguments.clone(),
+        }
+    }
+
     /// convert libraries vector into a string with class path.
     pub fn libs_as_string(
         &self,
@@ -140,7 +177,10 @@ impl Manifest {
         }
 
         let client_jar = root
-            .join(format!("versions/{0}/{0}.jar", version))
+            .join(format!(
+                "versions/{0}/{0}.jar",
+                self.jar.as_ref().unwrap_or(version)
+            ))
             .normalize()
             .to_string_lossy()
             .to_string();
@@ -299,8 +339,20 @@ impl Library {
             ":"
         }
     }
+    /// sha1 of the library jar. Libraries that are built by a loader installer have no
+    /// download url and are not checked.
+    pub(crate) fn artifact_sha1(&self) -> Option<&str> {
+        match &self.downloads {
+            Some(downloads) => downloads
+                .artifact
+                .as_ref()
+                .filter(|artifact| !artifact.url.is_empty() && !artifact.sha1.is_empty())
+                .map(|artifact| artifact.sha1.as_str()),
+            None => self.sha1.as_deref(),
+        }
+    }
     /// creates the absuolte path to this file
-    fn get_lib(&self, root: &std::path::Path) -> Result<Option<String>, Error> {
+    pub(crate) fn get_lib(&self, root: &std::path::Path) -> Result<Option<String>, Error> {
         let include = if let Some(rules) = &self.rules {
             parse_rules(None, rules)
         } else {
@@ -311,6 +363,15 @@ impl Library {
             return Ok(None);
         }
 
+        // legacy native only libraries (ex: lwjgl-platform) are extracted and not added to the classpath
+        if self
+            .downloads
+            .as_ref()
+            .is_some_and(|downloads| downloads.artifact.is_none())
+        {
+            return Ok(None);
+        }
+
         let lib = self.name.as_classpath();
 
         Ok(Some(
@@ -322,7 +383,7 @@ impl Library {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LibraryDownloads {
-    pub artifact: File,
+    pub artifact: Option<File>,
     pub classifiers: Option<std::collections::HashMap<String, File>>,
 }
 
@@ -559,6 +620,122 @@ mod tests {
         assert!(updated.libraries.len() == 1)
     }
 
+    #[test]
+    fn test_legacy_forge_inherit() {
+        init();
+        let vanilla: Manifest = serde_json::from_str(r#"{
+            "assetIndex": {
+                "id": "1.12",
+                "sha1": "1584b57c1a0b5e593fad1f5b8f78536ca640547b",
+                "size": 143138,
+                "totalSize": 129336389,
+                "url": "https://launchermeta.mojang.com/v1/packages/1584b57c1a0b5e593fad1f5b8f78536ca640547b/1.12.json"
+            },
+            "assets": "1.12",
+            "id": "1.12.2",
+            "javaVersion": { "component": "jre-legacy", "majorVersion": 8 },
+            "libraries": [
+                {
+                    "downloads": {
+                        "classifiers": {
+                            "natives-windows": {
+                                "path": "org/lwjgl/lwjgl/lwjgl-platform/2.9.4-nightly-20150209/lwjgl-platform-2.9.4-nightly-20150209-natives-windows.jar",
+                                "sha1": "b84d5102b9bbcabf3fd6e9f6d9f4a7d4d8d3b7a2",
+                                "size": 613748,
+                                "url": "https://libraries.minecraft.net/org/lwjgl/lwjgl/lwjgl-platform/2.9.4-nightly-20150209/lwjgl-platform-2.9.4-nightly-20150209-natives-windows.jar"
+                            }
+                        }
+                    },
+                    "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209",
+                    "natives": { "windows": "natives-windows" }
+                }
+            ],
+            "logging": {
+                "client": {
+                    "argument": "-Dlog4j.configurationFile=${path}",
+                    "file": {
+                        "id": "client-1.12.xml",
+                        "sha1": "bd65e7d2e3c237be76cfbef4c2405033d7f91521",
+                        "size": 888,
+                        "url": "https://launcher.mojang.com/v1/objects/bd65e7d2e3c237be76cfbef4c2405033d7f91521/client-1.12.xml"
+                    },
+                    "type": "log4j2-xml"
+                }
+            },
+            "mainClass": "net.minecraft.client.main.Main",
+            "minecraftArguments": "--username ${auth_player_name} --version ${version_name} --userProperties ${user_properties}",
+            "releaseTime": "2017-09-18T08:39:46+00:00",
+            "time": "2017-09-18T08:39:46+00:00",
+            "type": "release"
+        }"#).expect("Failed to parse vanilla manifest");
+
+        let forge: Manifest = serde_json::from_str(r#"{
+            "id": "1.12.2-forge-14.23.5.2860",
+            "inheritsFrom": "1.12.2",
+            "jar": "1.12.2",
+            "logging": {},
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "minecraftArguments": "--username ${auth_player_name} --version ${version_name} --tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker",
+            "libraries": [
+                { "name": "net.minecraft:launchwrapper:1.12" }
+            ],
+            "releaseTime": "2021-01-15T19:14:39+00:00",
+            "time": "2021-01-15T19:14:39+00:00",
+            "type": "release"
+        }"#).expect("Failed to parse forge manifest");
+
+        let manifest = vanilla.inherit(forge).expect("Failed to inherit");
+
+        assert_eq!(manifest.id, "1.12.2-forge-14.23.5.2860");
+        assert_eq!(manifest.jar.as_deref(), Some("1.12.2"));
+        assert!(
+            manifest
+                .logging
+                .as_ref()
+                .is_some_and(|x| x.client.is_some())
+        );
+        assert_eq!(manifest.assets.as_deref(), Some("1.12"));
+        assert!(
+            manifest
+                .minecraft_arguments
+                .as_ref()
+                .is_some_and(|x| x.ends_with("FMLTweaker"))
+        );
+
+        let arguments = manifest.launch_arguments();
+        assert_eq!(arguments.game.len(), 6);
+        assert!(!arguments.jvm.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_natives_not_in_classpath() {
+        let library: Library = serde_json::from_str(r#"
+        {
+            "downloads": {
+                "classifiers": {
+                    "natives-linux": {
+                        "path": "org/lwjgl/lwjgl/lwjgl-platform/2.9.4-nightly-20150209/lwjgl-platform-2.9.4-nightly-20150209-natives-linux.jar",
+                        "sha1": "931074f46c795d2f7b30ed6395df5715cfd7675b",
+                        "size": 578680,
+                        "url": "https://libraries.minecraft.net/org/lwjgl/lwjgl/lwjgl-platform/2.9.4-nightly-20150209/lwjgl-platform-2.9.4-nightly-20150209-natives-linux.jar"
+                    }
+                }
+            },
+            "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209",
+            "natives": { "linux": "natives-linux" }
+        }
+        "#).expect("Failed to parse library");
+
+        let root = std::env::temp_dir().join("libraries");
+
+        assert!(
+            library
+                .get_lib(&root)
+                .expect("Failed to get library path")
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_get_lib_no_download() {
         let library: Library = serde_json::from_str(r#"
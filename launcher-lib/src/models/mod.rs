@@ -1,3 +1,4 @@
 pub mod profile;
 pub mod queue;
+pub mod session;
 pub mod setting;
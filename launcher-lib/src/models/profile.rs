@@ -6,9 +6,10 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 #[derive(
-    Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, sqlx::Type,
+    Debug, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, sqlx::Type,
 )]
 pub enum Loader {
+    #[default]
     Vanilla,
     Forge,
     Fabric,
@@ -32,12 +33,6 @@ impl Display for Loader {
     }
 }
 
-impl Default for Loader {
-    fn default() -> Self {
-        Self::Vanilla
-    }
-}
-
 impl From<String> for Loader {
     fn from(value: String) -> Self {
         match value.to_lowercase().as_str() {
@@ -104,6 +99,10 @@ pub struct Profile {
     pub loader_version: Option<String>,
 
     pub java_args: Option<String>,
+    /// Heap sizes in MB
+    pub min_memory: Option<i64>,
+    pub max_memory: Option<i64>,
+    pub gc_preset: Option<String>,
 
     pub resolution_width: Option<String>,
     pub resolution_height: Option<String>,
@@ -1,7 +1,7 @@
 use crate::{database::RwDatabase, error::Result, utils::current_timestamp};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub enum QueueType {
     Client,
     Modpack,
@@ -11,15 +11,10 @@ pub enum QueueType {
     Datapack,
     CurseforgeModpack,
     Update,
+    #[default]
     Unknown,
 }
 
-impl Default for QueueType {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl From<String> for QueueType {
     fn from(value: String) -> Self {
         match value.to_lowercase().as_str() {
@@ -36,8 +31,9 @@ impl From<String> for QueueType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub enum QueueState {
+    #[default]
     Pending,
     Errored,
     Current,
@@ -46,12 +42,6 @@ pub enum QueueState {
     Unknown,
 }
 
-impl Default for QueueState {
-    fn default() -> Self {
-        Self::Pending
-    }
-}
-
 impl std::fmt::Display for QueueState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let value = match self {
@@ -116,10 +106,10 @@ impl QueueItem {
             }
         };
 
-        if let Some(item) = &result {
-            if !was_existing {
-                QueueItem::set_state(&item.id, QueueState::Current, rwdb).await?;
-            }
+        if let Some(item) = &result
+            && !was_existing
+        {
+            QueueItem::set_state(&item.id, QueueState::Current, rwdb).await?;
         }
 
         Ok(result)
@@ -135,6 +125,33 @@ impl QueueItem {
         Ok(())
     }
 
+    /// Postpone every pending item, used while the launcher is offline.
+    /// Returns the number of postponed items.
+    pub async fn postpone_pending(rwdb: &RwDatabase) -> Result<u64> {
+        let db = rwdb.write().await;
+
+        let result = sqlx::query(
+            "UPDATE download_queue SET state = 'POSTPONED' WHERE state IN ('PENDING', 'CURRENT');",
+        )
+        .execute(&db.0)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Move every postponed item back to pending.
+    /// Returns the number of resumed items.
+    pub async fn resume_postponed(rwdb: &RwDatabase) -> Result<u64> {
+        let db = rwdb.write().await;
+
+        let result =
+            sqlx::query("UPDATE download_queue SET state = 'PENDING' WHERE state = 'POSTPONED';")
+                .execute(&db.0)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn set_state(id: &str, state: QueueState, rwdb: &RwDatabase) -> Result<()> {
         let db = rwdb.write().await;
 
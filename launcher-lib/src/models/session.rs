@@ -0,0 +1,308 @@
+use crate::{database::RwDatabase, error::Result};
+use serde::Serialize;
+
+/// Timestamps are stored in the same format as javascript's `Date.toISOString`
+const NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ','now')";
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PlaySession {
+    pub id: i64,
+    pub profile_id: String,
+    /// uuid of the process the session belongs to
+    pub process_id: String,
+    pub started: String,
+    /// Not set if the game is still running or the launcher was closed before the game exited.
+    pub ended: Option<String>,
+    pub exit_code: Option<i64>,
+}
+
+/// Total play time of a profile in seconds
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Playtime {
+    pub profile_id: String,
+    pub sessions: i64,
+    pub seconds: i64,
+}
+
+impl PlaySession {
+    /// Start a session for a started game and update the profile's last played time.
+    pub async fn start(profile_id: &str, process_id: &str, rwdb: &RwDatabase) -> Result<()> {
+        let db = rwdb.write().await;
+
+        sqlx::query(&format!(
+            "INSERT INTO play_sessions (profile_id, process_id, started) VALUES (?,?,{NOW});"
+        ))
+        .bind(profile_id)
+        .bind(process_id)
+        .execute(&db.0)
+        .await?;
+
+        sqlx::query(&format!(
+            "UPDATE profiles SET last_played = {NOW} WHERE id = ?;"
+        ))
+        .bind(profile_id)
+        .execute(&db.0)
+        .await?;
+
+        Ok(())
+    }
+
+    /// End the session of a process and update the profile's last played time.
+    pub async fn end(process_id: &str, exit_code: Option<i32>, rwdb: &RwDatabase) -> Result<()> {
+        let db = rwdb.write().await;
+
+        let profile_id: Option<String> = sqlx::query_scalar(&format!(
+            "UPDATE play_sessions SET ended = {NOW}, exit_code = ? WHERE process_id = ? AND ended IS NULL RETURNING profile_id;"
+        ))
+        .bind(exit_code)
+        .bind(process_id)
+        .fetch_optional(&db.0)
+        .await?;
+
+        if let Some(profile_id) = profile_id {
+            sqlx::query(&format!(
+                "UPDATE profiles SET last_played = {NOW} WHERE id = ?;"
+            ))
+            .bind(profile_id)
+            .execute(&db.0)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// End the sessions that were left open when the launcher was closed while the game was running.
+    /// The time they ended is unknown so they end when they started and do not add to the play time.
+    /// Sessions of `running` processes, that were rescued from the process cache, are kept open.
+    /// Returns the number of closed sessions.
+    pub async fn close_dangling(running: &[String], rwdb: &RwDatabase) -> Result<u64> {
+        let db = rwdb.write().await;
+
+        let open: Vec<String> =
+            sqlx::query_scalar("SELECT process_id FROM play_sessions WHERE ended IS NULL;")
+                .fetch_all(&db.0)
+                .await?;
+
+        let mut closed = 0;
+        for process_id in open.iter().filter(|id| !running.contains(id)) {
+            closed += sqlx::query(
+                "UPDATE play_sessions SET ended = started WHERE process_id = ? AND ended IS NULL;",
+            )
+            .bind(process_id)
+            .execute(&db.0)
+            .await?
+            .rows_affected();
+        }
+
+        Ok(closed)
+    }
+
+    /// Get the most recent sessions of a profile
+    pub async fn list(profile_id: &str, limit: i64, rwdb: &RwDatabase) -> Result<Vec<PlaySession>> {
+        let db = rwdb.read().await;
+
+        let sessions = sqlx::query_as(
+            "SELECT * FROM play_sessions WHERE profile_id = ? ORDER BY started DESC LIMIT ?;",
+        )
+        .bind(profile_id)
+        .bind(limit)
+        .fetch_all(&db.0)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Get the total play time of every profile. Sessions that have not ended are not counted.
+    pub async fn total_playtime(rwdb: &RwDatabase) -> Result<Vec<Playtime>> {
+        let db = rwdb.read().await;
+
+        let playtime = sqlx::query_as(
+            "SELECT profile_id, COUNT(*) AS sessions, CAST(ROUND(COALESCE(SUM((julianday(ended) - julianday(started)) * 86400), 0)) AS INTEGER) AS seconds FROM play_sessions WHERE ended IS NOT NULL GROUP BY profile_id;",
+        )
+        .fetch_all(&db.0)
+        .await?;
+
+        Ok(playtime)
+    }
+
+    /// Get the total play time of a profile in seconds
+    pub async fn total_playtime_for(profile_id: &str, rwdb: &RwDatabase) -> Result<i64> {
+        let db = rwdb.read().await;
+
+        let seconds = sqlx::query_scalar(
+            "SELECT CAST(ROUND(COALESCE(SUM((julianday(ended) - julianday(started)) * 86400), 0)) AS INTEGER) FROM play_sessions WHERE profile_id = ? AND ended IS NOT NULL;",
+        )
+        .bind(profile_id)
+        .fetch_one(&db.0)
+        .await?;
+
+        Ok(seconds)
+    }
+
+    /// Get the play time of every profile between two ISO 8601 timestamps.
+    /// Sessions that overlap the start or end of the window only count the time inside of it.
+    pub async fn playtime_between(
+        from: &str,
+        to: &str,
+        rwdb: &RwDatabase,
+    ) -> Result<Vec<Playtime>> {
+        let db = rwdb.read().await;
+
+        let playtime = sqlx::query_as(
+            "SELECT profile_id, COUNT(*) AS sessions, CAST(ROUND(SUM(MAX(0, MIN(julianday(ended), julianday(?2)) - MAX(julianday(started), julianday(?1))) * 86400)) AS INTEGER) AS seconds FROM play_sessions WHERE ended IS NOT NULL AND julianday(ended) > julianday(?1) AND julianday(started) < julianday(?2) GROUP BY profile_id;",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&db.0)
+        .await?;
+
+        Ok(playtime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn database() -> RwDatabase {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to open database");
+        let db = Database(pool);
+        db.run_migrations(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations"))
+            .await
+            .expect("Failed to run migrations");
+        sqlx::query(
+            "INSERT INTO profiles (id, name, date_created, version, loader) VALUES ('a','a','2026-10-01T00:00:00.000Z','1.21.1','vanilla'), ('b','b','2026-10-01T00:00:00.000Z','1.21.1','vanilla');",
+        )
+        .execute(&db.0)
+        .await
+        .expect("Failed to insert profiles");
+
+        RwDatabase::new(db)
+    }
+
+    async fn insert(
+        profile_id: &str,
+        process_id: &str,
+        started: &str,
+        ended: Option<&str>,
+        db: &RwDatabase,
+    ) {
+        sqlx::query(
+            "INSERT INTO play_sessions (profile_id, process_id, started, ended) VALUES (?,?,?,?);",
+        )
+        .bind(profile_id)
+        .bind(process_id)
+        .bind(started)
+        .bind(ended)
+        .execute(&db.read().await.0)
+        .await
+        .expect("Failed to insert session");
+    }
+
+    #[tokio::test]
+    async fn test_start_end() {
+        let db = database().await;
+
+        PlaySession::start("a", "process", &db).await.unwrap();
+        let sessions = PlaySession::list("a", 10, &db).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].process_id, "process");
+        assert!(sessions[0].ended.is_none());
+
+        let last_played: Option<String> =
+            sqlx::query_scalar("SELECT last_played FROM profiles WHERE id = 'a';")
+                .fetch_one(&db.read().await.0)
+                .await
+                .unwrap();
+        assert!(last_played.is_some());
+
+        PlaySession::end("process", Some(1), &db).await.unwrap();
+        // a ended session is not ended again
+        PlaySession::end("process", Some(0), &db).await.unwrap();
+        let sessions = PlaySession::list("a", 10, &db).await.unwrap();
+        assert!(sessions[0].ended.is_some());
+        assert_eq!(sessions[0].exit_code, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_playtime() {
+        let db = database().await;
+        insert(
+            "a",
+            "1",
+            "2026-10-01T10:00:00.000Z",
+            Some("2026-10-01T11:00:00.000Z"),
+            &db,
+        )
+        .await;
+        insert(
+            "a",
+            "2",
+            "2026-10-02T10:00:00.000Z",
+            Some("2026-10-02T10:30:00.000Z"),
+            &db,
+        )
+        .await;
+        insert(
+            "b",
+            "3",
+            "2026-10-01T23:30:00.000Z",
+            Some("2026-10-02T00:30:00.000Z"),
+            &db,
+        )
+        .await;
+        insert("b", "4", "2026-10-03T10:00:00.000Z", None, &db).await;
+
+        let mut total = PlaySession::total_playtime(&db).await.unwrap();
+        total.sort_by(|a, b| a.profile_id.cmp(&b.profile_id));
+        assert_eq!(total.len(), 2);
+        assert_eq!((total[0].sessions, total[0].seconds), (2, 5400));
+        assert_eq!((total[1].sessions, total[1].seconds), (1, 3600));
+        assert_eq!(
+            PlaySession::total_playtime_for("a", &db).await.unwrap(),
+            5400
+        );
+        assert_eq!(PlaySession::total_playtime_for("c", &db).await.unwrap(), 0);
+
+        // only the time inside of the window is counted
+        let mut day = PlaySession::playtime_between(
+            "2026-10-01T00:00:00.000Z",
+            "2026-10-02T00:00:00.000Z",
+            &db,
+        )
+        .await
+        .unwrap();
+        day.sort_by(|a, b| a.profile_id.cmp(&b.profile_id));
+        assert_eq!(day.len(), 2);
+        assert_eq!((day[0].sessions, day[0].seconds), (1, 3600));
+        assert_eq!((day[1].sessions, day[1].seconds), (1, 1800));
+    }
+
+    #[tokio::test]
+    async fn test_close_dangling() {
+        let db = database().await;
+        insert("a", "1", "2026-10-01T10:00:00.000Z", None, &db).await;
+        insert("b", "2", "2026-10-01T10:00:00.000Z", None, &db).await;
+
+        let closed = PlaySession::close_dangling(&["2".to_string()], &db)
+            .await
+            .unwrap();
+        assert_eq!(closed, 1);
+
+        let sessions = PlaySession::list("a", 10, &db).await.unwrap();
+        assert_eq!(
+            sessions[0].ended.as_deref(),
+            Some("2026-10-01T10:00:00.000Z")
+        );
+        assert_eq!(PlaySession::total_playtime_for("a", &db).await.unwrap(), 0);
+        // the session of the running process is still open
+        let sessions = PlaySession::list("b", 10, &db).await.unwrap();
+        assert!(sessions[0].ended.is_none());
+    }
+}
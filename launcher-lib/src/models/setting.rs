@@ -57,6 +57,31 @@ impl Setting {
             .await?;
         Ok(())
     }
+    pub async fn update(
+        key: &str,
+        value: String,
+        metadata: Option<String>,
+        rwdb: &RwDatabase,
+    ) -> Result<()> {
+        let db = rwdb.write().await;
+
+        sqlx::query("UPDATE settings SET value = ?, metadata = ? WHERE key = ?;")
+            .bind(value)
+            .bind(metadata)
+            .bind(key)
+            .execute(&db.0)
+            .await?;
+        Ok(())
+    }
+    pub async fn delete(key: &str, rwdb: &RwDatabase) -> Result<()> {
+        let db = rwdb.write().await;
+
+        sqlx::query("DELETE FROM settings WHERE key = ?;")
+            .bind(key)
+            .execute(&db.0)
+            .await?;
+        Ok(())
+    }
     pub async fn has(key: &str, rwdb: &RwDatabase) -> Result<bool> {
         let db = rwdb.read().await;
 
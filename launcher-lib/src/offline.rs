@@ -0,0 +1,297 @@
+//! Offline mode
+//!
+//! The launcher is offline when `option.offline_mode` is `TRUE` or when the last [`check_connectivity`]
+//! could not reach the metadata server. Failures of single requests do not change the offline state. While offline no requests are sent, version and loader lists are
+//! read from the copies kept in `runtime/cache` and files that are not on disk can not be downloaded.
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tokio::fs;
+
+use crate::database::RwDatabase;
+use crate::error::{Error, Result};
+use crate::installer::endpoints::{self, Endpoint};
+use crate::installer::http;
+use crate::models::setting::Setting;
+
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(5);
+const CONNECTIVITY_PROBE: &str = "/mc/game/version_manifest_v2.json";
+
+/// offline mode was turned on in the settings
+static FORCED: AtomicBool = AtomicBool::new(false);
+/// the last connectivity probe failed
+static UNREACHABLE: AtomicBool = AtomicBool::new(false);
+/// offline state seen by the last call of [`went_online`]
+static WAS_OFFLINE: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    static ref CACHE_DIRECTORY: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+pub fn is_offline() -> bool {
+    FORCED.load(Ordering::SeqCst) || UNREACHABLE.load(Ordering::SeqCst)
+}
+
+/// Whether offline mode was turned on in the settings
+pub fn is_forced() -> bool {
+    FORCED.load(Ordering::SeqCst)
+}
+
+pub fn set_offline_mode(enabled: bool) {
+    FORCED.store(enabled, Ordering::SeqCst);
+}
+
+/// Set the directory that fetched metadata is cached in
+pub fn set_cache_directory(directory: PathBuf) {
+    *CACHE_DIRECTORY
+        .write()
+        .unwrap_or_else(|err| err.into_inner()) = Some(directory);
+}
+
+pub async fn configure_from_settings(db: &RwDatabase) -> Result<()> {
+    let forced = Setting::get("option.offline_mode", db)
+        .await?
+        .is_some_and(|setting| setting.value == "TRUE");
+    set_offline_mode(forced);
+
+    if let Some(path) = Setting::get("path.app", db).await? {
+        set_cache_directory(PathBuf::from(path.value).join("runtime").join("cache"));
+    }
+
+    Ok(())
+}
+
+/// Probe the metadata server and update the offline state.
+/// Returns `true` when the launcher is online.
+pub async fn check_connectivity() -> bool {
+    if is_forced() {
+        return false;
+    }
+
+    let url = endpoints::rewrite(&Endpoint::LauncherMeta.url(CONNECTIVITY_PROBE));
+    let reachable = http::client()
+        .head(url)
+        .timeout(CONNECTIVITY_TIMEOUT)
+        .send()
+        .await
+        .is_ok();
+
+    if UNREACHABLE.swap(!reachable, Ordering::SeqCst) == reachable {
+        info!(
+            "Metadata server is {}",
+            if reachable {
+                "reachable"
+            } else {
+                "unreachable"
+            }
+        );
+    }
+
+    reachable
+}
+
+/// Whether the launcher came back online since the last call
+pub fn went_online() -> bool {
+    let offline = is_offline();
+    WAS_OFFLINE.swap(offline, Ordering::SeqCst) && !offline
+}
+
+fn cache_directory() -> Option<PathBuf> {
+    CACHE_DIRECTORY
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
+
+fn cache_path(directory: &Path, url: &str) -> PathBuf {
+    let mut hasher = Sha1::new();
+    hasher.update(url.as_bytes());
+    directory.join(format!("{:x}", hasher.finalize()))
+}
+
+/// Response headers used to ask the server whether a cached document changed
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedDocument {
+    body: String,
+    validators: Validators,
+}
+
+/// The body is stored in a file named by the sha1 of the url, the validators next to it.
+async fn read_cache(directory: Option<&Path>, url: &str) -> Option<CachedDocument> {
+    let path = cache_path(directory?, url);
+    let body = fs::read_to_string(&path).await.ok()?;
+    let validators = fs::read(path.with_extension("validators"))
+        .await
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default();
+
+    Some(CachedDocument { body, validators })
+}
+
+async fn write_cache(directory: Option<&Path>, url: &str, document: &CachedDocument) -> Result<()> {
+    let Some(directory) = directory else {
+        return Ok(());
+    };
+    fs::create_dir_all(directory).await?;
+    let path = cache_path(directory, url);
+    let validators = path.with_extension("validators");
+
+    // the old validators are removed first, a body without validators is downloaded again
+    if let Err(err) = fs::remove_file(&validators).await
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        return Err(err.into());
+    }
+    replace_file(&path, document.body.as_bytes()).await?;
+    replace_file(&validators, &serde_json::to_vec(&document.validators)?).await?;
+    Ok(())
+}
+
+/// Write to a temp file and rename it over the file, so a interrupted write never leaves a partial file.
+async fn replace_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+
+    fs::write(&temp, contents).await?;
+    if let Err(err) = fs::rename(&temp, path).await {
+        let _ = fs::remove_file(&temp).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Fetch a document, returns `None` when the server reports that the cached copy is still current.
+async fn fetch_text(url: &str, cached: Option<&Validators>) -> Result<Option<CachedDocument>> {
+    let mut request = http::client().get(endpoints::rewrite(url));
+    if let Some(validators) = cached {
+        if let Some(etag) = &validators.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await?.error_for_status()?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let validators = Validators {
+        etag: header(reqwest::header::ETAG),
+        last_modified: header(reqwest::header::LAST_MODIFIED),
+    };
+
+    Ok(Some(CachedDocument {
+        body: response.text().await?,
+        validators,
+    }))
+}
+
+/// Get a text document from a metadata endpoint.
+/// The last response is kept and served while offline or when the request fails,
+/// online it is only downloaded again when the server reports a change with its `ETag` or `Last-Modified`.
+pub async fn fetch_cached(url: &str) -> Result<String> {
+    fetch_with_cache(cache_directory().as_deref(), is_offline(), url).await
+}
+
+async fn fetch_with_cache(directory: Option<&Path>, offline: bool, url: &str) -> Result<String> {
+    let cached = read_cache(directory, url).await;
+
+    if offline {
+        debug!("Offline, reading {} from cache", url);
+        return cached
+            .map(|document| document.body)
+            .ok_or_else(|| Error::Offline(url.to_string()));
+    }
+
+    match fetch_text(url, cached.as_ref().map(|document| &document.validators)).await {
+        Ok(Some(document)) => {
+            if let Err(err) = write_cache(directory, url, &document).await {
+                warn!("Failed to cache {}: {}", url, err);
+            }
+            Ok(document.body)
+        }
+        Ok(None) => {
+            debug!("{} was not modified, using cached copy", url);
+            cached
+                .map(|document| document.body)
+                .ok_or_else(|| Error::NotFound(format!("cached copy of {}", url)))
+        }
+        Err(err) => match cached {
+            Some(document) => {
+                warn!("Failed to fetch {}, using cached copy: {}", url, err);
+                Ok(document.body)
+            }
+            None => Err(err),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_cached() {
+        let temp = std::env::temp_dir().join("offline_cache_test");
+        if temp.exists() {
+            fs::remove_dir_all(&temp)
+                .await
+                .expect("Failed to clear dir");
+        }
+
+        let url = "https://meta.fabricmc.net/v2/versions/loader";
+        let result = fetch_with_cache(Some(&temp), true, url).await;
+        assert!(matches!(result, Err(Error::Offline(_))));
+
+        let document = CachedDocument {
+            body: "[]".to_string(),
+            validators: Validators {
+                etag: Some("\"abc\"".to_string()),
+                last_modified: None,
+            },
+        };
+        write_cache(Some(&temp), url, &document)
+            .await
+            .expect("Failed to write cache");
+        let cached = read_cache(Some(&temp), url)
+            .await
+            .expect("Failed to read cache");
+        assert_eq!(cached.validators, document.validators);
+        // only the body and its validators are left
+        let mut entries = fs::read_dir(&temp).await.unwrap();
+        let mut count = 0;
+        while entries.next_entry().await.unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 2);
+
+        let body = fetch_with_cache(Some(&temp), true, url)
+            .await
+            .expect("Failed to read cache");
+        assert_eq!(body, "[]");
+
+        fs::remove_dir_all(&temp).await.unwrap();
+    }
+}
@@ -1,7 +1,15 @@
 use log::error;
-use std::{collections::HashMap, path::Path, time::Duration};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
 use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, BufReader},
     process::{Child, Command},
+    sync::broadcast,
     task::{self},
 };
 use uuid::Uuid;
@@ -9,7 +17,10 @@ use uuid::Uuid;
 use crate::{
     database::RwDatabase,
     error::{Error, Result},
-    launcher::logs,
+    launcher::{
+        crash::{self, CrashReport},
+        ready::{ReadySignal, ReadyStrategy, ReadyWatcher},
+    },
 };
 
 #[derive(Default)]
@@ -52,6 +63,11 @@ impl Processes {
     pub fn get(&self, uuid: &str) -> Option<&Process> {
         self.state.get(uuid)
     }
+    pub fn get_by_profile(&self, profile_id: &str) -> Option<&Process> {
+        self.ptu
+            .get(profile_id)
+            .and_then(|uuid| self.state.get(uuid))
+    }
     pub fn get_mut(&mut self, profile_id: &str) -> Option<&mut Process> {
         if let Some(uuid) = self.ptu.get(profile_id) {
             self.state.get_mut(uuid)
@@ -132,11 +148,156 @@ impl Processes {
     }
 }
 
-fn get_modified_time(file: &Path, now: &std::time::SystemTime) -> std::io::Result<bool> {
-    let metadata = file.metadata()?;
-    let modified = metadata.modified()?;
+/// Result of waiting for a game to start
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum LaunchStatus {
+    /// The game started, with the signal that was used to detect it
+    Ready(ReadySignal),
+    /// The game was stopped before it was ready
+    Exited,
+    /// The game did not start within the max launch wait
+    Timeout,
+    IoError,
+}
+
+/// Max number of output lines kept for a process
+const MAX_OUTPUT_LINES: usize = 1000;
+const OUTPUT_CHANNEL_SIZE: usize = 256;
+/// Max bytes of a output line that are kept, the rest of a longer line is dropped
+const MAX_LINE_LENGTH: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
 
-    Ok(&modified > now)
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// The stdout and stderr of a running process.
+/// Recent lines are kept in a ring buffer so late subscribers can get the lines they missed.
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    sender: broadcast::Sender<OutputLine>,
+    buffer: Arc<Mutex<VecDeque<OutputLine>>>,
+}
+
+impl ProcessOutput {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(OUTPUT_CHANNEL_SIZE);
+        Self {
+            sender,
+            buffer: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_OUTPUT_LINES))),
+        }
+    }
+
+    fn push(&self, line: OutputLine) {
+        match self.buffer.lock() {
+            Ok(mut buffer) => {
+                if buffer.len() >= MAX_OUTPUT_LINES {
+                    buffer.pop_front();
+                }
+                buffer.push_back(line.clone());
+                // a send error only means no one is listening.
+                let _ = self.sender.send(line);
+            }
+            Err(err) => log::error!("Process output buffer poisoned: {}", err),
+        }
+    }
+
+    /// Get the buffered lines and a receiver for any new lines.
+    pub fn subscribe(&self) -> (Vec<OutputLine>, broadcast::Receiver<OutputLine>) {
+        // hold the buffer lock so that no line is missed or sent twice.
+        match self.buffer.lock() {
+            Ok(buffer) => (buffer.iter().cloned().collect(), self.sender.subscribe()),
+            Err(_) => (Vec::new(), self.sender.subscribe()),
+        }
+    }
+
+    /// Get the buffered lines
+    pub fn recent(&self) -> Vec<OutputLine> {
+        self.buffer
+            .lock()
+            .map(|buffer| buffer.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Read lines from a process pipe until it closes.
+    fn drain<R>(&self, pipe: R, stream: OutputStream)
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let output = self.clone();
+        task::spawn(async move { output.read_lines(pipe, stream).await });
+    }
+
+    /// The pipe has to be read until it closes or the process blocks once the pipe is full,
+    /// so lines that are not valid utf-8 are kept lossily, lines longer than [`MAX_LINE_LENGTH`] are truncated
+    /// and after a read error the rest is discarded.
+    async fn read_lines<R>(&self, pipe: R, stream: OutputStream)
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut reader = BufReader::new(pipe);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            match read_line_capped(&mut reader, &mut buffer).await {
+                Ok((0, _)) => break,
+                Ok((_, truncated)) => {
+                    let line = String::from_utf8_lossy(&buffer);
+                    let mut line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if truncated {
+                        line.push_str(" [truncated]");
+                    }
+                    self.push(OutputLine { stream, line });
+                }
+                Err(err) => {
+                    log::error!("Failed to read process output: {}", err);
+                    if let Err(err) = tokio::io::copy(&mut reader, &mut tokio::io::sink()).await {
+                        log::error!("Failed to discard process output: {}", err);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Read a line into the buffer, keeping at most [`MAX_LINE_LENGTH`] bytes of it.
+/// Returns the number of bytes that were read and whether the line was truncated.
+async fn read_line_capped<R>(reader: &mut R, buffer: &mut Vec<u8>) -> std::io::Result<(usize, bool)>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut read = 0;
+    let mut truncated = false;
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok((read, truncated));
+        }
+
+        let (length, done) = match available.iter().position(|byte| *byte == b'\n') {
+            Some(end) => (end + 1, true),
+            None => (available.len(), false),
+        };
+        let kept = length.min(MAX_LINE_LENGTH.saturating_sub(buffer.len()));
+        truncated |= kept < length;
+        buffer.extend_from_slice(&available[..kept]);
+
+        reader.consume(length);
+        read += length;
+        if done {
+            return Ok((read, truncated));
+        }
+    }
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -150,6 +311,13 @@ pub struct Process {
     pub child: InstanceType,
     #[sqlx(skip)]
     pub startup_watcher: Option<tokio_util::sync::CancellationToken>,
+    /// Output of the process, only available for processes started by this instance of the launcher
+    #[sqlx(skip)]
+    pub output: Option<ProcessOutput>,
+    #[sqlx(skip)]
+    pub game_directory: Option<PathBuf>,
+    #[sqlx(skip)]
+    pub started_at: Option<SystemTime>,
 }
 
 impl Process {
@@ -158,20 +326,31 @@ impl Process {
         args: Vec<String>,
         profile_id: String,
         game_directory: &Path,
-        on_ready: tokio::sync::oneshot::Sender<String>,
+        on_ready: tokio::sync::oneshot::Sender<LaunchStatus>,
         max_wait_to_start: Option<u64>,
+        ready_strategy: ReadyStrategy,
     ) -> Result<Self> {
         let uuid = Uuid::new_v4().to_string();
 
-        let now = std::time::SystemTime::now();
+        let now = SystemTime::now();
 
-        let ps = Command::new(&exe)
+        let mut ps = Command::new(&exe)
             .current_dir(game_directory)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .args(args)
             .spawn()?;
 
+        // the pipes have to be read or the game will hang once the pipe buffer is full.
+        let output = ProcessOutput::new();
+        let (_, output_rx) = output.subscribe();
+        if let Some(stdout) = ps.stdout.take() {
+            output.drain(stdout, OutputStream::Stdout);
+        }
+        if let Some(stderr) = ps.stderr.take() {
+            output.drain(stderr, OutputStream::Stderr);
+        }
+
         // =======================
         // START: get-process-info
         // =======================
@@ -201,65 +380,48 @@ impl Process {
 
         let cancellation_token = tokio_util::sync::CancellationToken::new();
 
-        let log_file = game_directory.join("logs/latest.log");
+        let mut ready_watcher = ReadyWatcher::new(
+            ready_strategy,
+            pid.as_u32(),
+            game_directory.join("logs/latest.log"),
+            now,
+            Some(output_rx),
+        );
         let token = cancellation_token.clone();
         task::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(250));
             let watched = pid;
             // default is wait about 1min
             let max_ticks = max_wait_to_start.unwrap_or(1200) + 8;
-            let mut log_file_ready = false;
             let mut tick_count = 0;
-            let mut cursor = 0;
 
-            'watcher: loop {
+            loop {
                 interval.tick().await;
 
                 if token.is_cancelled() {
-                    if let Err(err) = on_ready.send("process::exited".to_string()) {
-                        log::error!("Failed to send on ready. {}", err);
+                    if let Err(err) = on_ready.send(LaunchStatus::Exited) {
+                        log::error!("Failed to send on ready. {:?}", err);
                     }
                     break;
                 }
 
                 tick_count += 1;
 
-                if !log_file_ready {
-                    if log_file.exists() {
-                        match get_modified_time(&log_file, &now) {
-                            Ok(ready) => {
-                                log_file_ready = ready;
-                            }
-                            Err(err) => {
-                                error!("{}", err);
-                                if let Err(err) = on_ready.send("error::io".to_string()) {
-                                    log::error!("Failed to send on ready. {}", err);
-                                }
-                                break 'watcher;
-                            }
+                match ready_watcher.poll().await {
+                    Ok(Some(signal)) => {
+                        log::debug!("Game is ready! Signal: {:?}", signal);
+                        if let Err(err) = on_ready.send(LaunchStatus::Ready(signal)) {
+                            log::error!("Failed to send on ready. {:?}", err);
                         }
+                        break;
                     }
-                } else {
-                    match logs::get_latest_log_cursor(&log_file, cursor).await {
-                        Ok(result) => {
-                            cursor = result.cursor;
-                            for x in result.line.split('\n') {
-                                if x.contains("[Render thread/INFO]: Sound engine started") {
-                                    log::debug!("Game is ready!");
-                                    if let Err(err) = on_ready.send("ok".to_string()) {
-                                        log::error!("Failed to send on ready. {}", err);
-                                    }
-                                    break 'watcher;
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            log::error!("Launch watcher errored! {}", err);
-                            if let Err(err) = on_ready.send("error::io".to_string()) {
-                                log::error!("Failed to send on ready. {}", err);
-                            }
-                            break 'watcher;
+                    Ok(None) => {}
+                    Err(err) => {
+                        error!("Launch watcher errored! {}", err);
+                        if let Err(err) = on_ready.send(LaunchStatus::IoError) {
+                            log::error!("Failed to send on ready. {:?}", err);
                         }
+                        break;
                     }
                 }
 
@@ -272,12 +434,13 @@ impl Process {
                     }
 
                     log::debug!("Game failed to launch killing.");
+                    token.cancel();
 
-                    if let Err(err) = on_ready.send("error::timeout".to_string()) {
-                        log::error!("Failed to send on ready. {}", err);
+                    if let Err(err) = on_ready.send(LaunchStatus::Timeout) {
+                        log::error!("Failed to send on ready. {:?}", err);
                     }
 
-                    break 'watcher;
+                    break;
                 }
             }
         });
@@ -290,14 +453,15 @@ impl Process {
             profile_id,
             child,
             startup_watcher: Some(cancellation_token),
+            output: Some(output),
+            game_directory: Some(game_directory.to_path_buf()),
+            started_at: Some(now),
         })
     }
     pub async fn status(&mut self) -> Result<Option<i32>> {
         match &mut self.child {
             InstanceType::Unknown => Ok(None),
-            InstanceType::Full(child) => {
-                Ok(child.try_wait()?.map(|x| x.code().unwrap_or_default()))
-            }
+            InstanceType::Full(child) => Ok(child.try_wait()?.map(exit_code)),
             InstanceType::Partial(pid) => {
                 let mut system = sysinfo::System::new();
                 let id = sysinfo::Pid::from_u32(*pid);
@@ -328,15 +492,48 @@ impl Process {
             InstanceType::Partial(pid) => {
                 let mut system = sysinfo::System::new();
                 let id = sysinfo::Pid::from_u32(*pid);
-                if system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[id]), true) == 1 {
-                    if let Some(process) = system.process(id) {
-                        process.kill();
-                    }
+                if system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[id]), true) == 1
+                    && let Some(process) = system.process(id)
+                {
+                    process.kill();
                 }
                 Ok(())
             }
         }
     }
+    /// Check if the process was stopped by the launcher
+    pub fn was_killed(&self) -> bool {
+        self.startup_watcher
+            .as_ref()
+            .is_some_and(|watcher| watcher.is_cancelled())
+    }
+    /// Get what is needed to find the crash report of this process.
+    /// It is only collected here, so the crash files can be read after the process list is unlocked.
+    /// Returns `None` if the process was not started by this instance of the launcher.
+    pub fn crash_context(&self) -> Option<CrashContext> {
+        let (Some(game_directory), Some(started_at)) = (&self.game_directory, self.started_at)
+        else {
+            return None;
+        };
+
+        let output = self
+            .output
+            .as_ref()
+            .map(|output| {
+                output
+                    .recent()
+                    .into_iter()
+                    .map(|item| item.line)
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+
+        Some(CrashContext {
+            game_directory: game_directory.clone(),
+            started_at,
+            output,
+        })
+    }
     pub fn pid(&self) -> Option<u32> {
         match &self.child {
             InstanceType::Unknown => None,
@@ -346,15 +543,107 @@ impl Process {
     }
 }
 
-#[derive(Debug)]
+/// Game directory, start time and last output of a crashed process
+#[derive(Debug, Clone)]
+pub struct CrashContext {
+    pub game_directory: PathBuf,
+    pub started_at: SystemTime,
+    pub output: Vec<String>,
+}
+
+impl CrashContext {
+    /// Find and classify the crash report of the process
+    pub async fn analyze(&self) -> Result<CrashReport> {
+        crash::analyze_crash(&self.game_directory, self.started_at, &self.output).await
+    }
+}
+
+/// Exit code of a process, a process that was killed by a signal gets `128 + signal` like in a shell.
+/// A jvm that crashed with a SIGSEGV or SIGABRT has to count as a abnormal exit so its crash report is read.
+fn exit_code(status: std::process::ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    -1
+}
+
+#[derive(Debug, Default)]
 pub enum InstanceType {
+    #[default]
     Unknown,
     Full(Child),
     Partial(u32),
 }
 
-impl Default for InstanceType {
-    fn default() -> Self {
-        Self::Unknown
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_buffer_is_bounded() {
+        let output = ProcessOutput::new();
+        for i in 0..(MAX_OUTPUT_LINES + 10) {
+            output.push(OutputLine {
+                stream: OutputStream::Stdout,
+                line: i.to_string(),
+            });
+        }
+
+        let (history, mut rx) = output.subscribe();
+        assert_eq!(history.len(), MAX_OUTPUT_LINES);
+        assert_eq!(history[0].line, "10");
+
+        output.push(OutputLine {
+            stream: OutputStream::Stderr,
+            line: "next".to_string(),
+        });
+        assert_eq!(rx.try_recv().expect("Failed to receive").line, "next");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_signal() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // wait status of a process that was killed by SIGSEGV
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(11)), 139);
+        // wait status of `exit(1)`
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(1 << 8)), 1);
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(0)), 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_lines_invalid_utf8() {
+        let output = ProcessOutput::new();
+        let pipe: &[u8] = b"first\r\ninvalid \xff\xfe line\nlast";
+        output.read_lines(pipe, OutputStream::Stdout).await;
+
+        let lines: Vec<String> = output.recent().into_iter().map(|line| line.line).collect();
+        assert_eq!(lines, ["first", "invalid \u{fffd}\u{fffd} line", "last"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_lines_truncated() {
+        let output = ProcessOutput::new();
+        let mut data = vec![b'a'; MAX_LINE_LENGTH * 4];
+        data.extend_from_slice(b"\nnext\n");
+        output
+            .read_lines(data.as_slice(), OutputStream::Stdout)
+            .await;
+
+        let lines: Vec<String> = output.recent().into_iter().map(|line| line.line).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_LINE_LENGTH + " [truncated]".len());
+        assert!(lines[0].ends_with(" [truncated]"));
+        assert_eq!(lines[1], "next");
     }
 }
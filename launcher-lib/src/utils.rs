@@ -13,6 +13,25 @@ pub fn current_timestamp() -> Result<String, time::error::Format> {
     offset.format(&Iso8601::DATE_TIME)
 }
 
+/// Max number of items to list in an error message
+const MAX_LISTED_ITEMS: usize = 5;
+
+/// Comma separated list of the first items for an error message
+pub(crate) fn describe_list<T: std::fmt::Display>(items: &[T]) -> String {
+    let mut message = items
+        .iter()
+        .take(MAX_LISTED_ITEMS)
+        .map(|item| item.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    if items.len() > MAX_LISTED_ITEMS {
+        message.push_str(&format!(" and {} more", items.len() - MAX_LISTED_ITEMS));
+    }
+
+    message
+}
+
 /// Get the systems total memory in GB
 pub fn get_ram_gb() -> u64 {
     let info = sysinfo::System::new_all();
@@ -21,7 +40,7 @@ pub fn get_ram_gb() -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::get_ram_gb;
+    use super::{describe_list, get_ram_gb};
 
     #[test]
     fn test_get_ram_gb() {
@@ -29,4 +48,13 @@ mod tests {
 
         println!("{}", value);
     }
+
+    #[test]
+    fn test_describe_list() {
+        assert_eq!(describe_list(&["a", "b"]), "a, b");
+        assert_eq!(
+            describe_list(&[1, 2, 3, 4, 5, 6, 7]),
+            "1, 2, 3, 4, 5 and 2 more"
+        );
+    }
 }
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS play_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile_id TEXT NOT NULL,
+    process_id TEXT NOT NULL,
+    started DATETIME NOT NULL,
+    ended DATETIME,
+    exit_code INTEGER
+);
+CREATE INDEX IF NOT EXISTS play_sessions_profile ON play_sessions (profile_id);
+
+CREATE TRIGGER IF NOT EXISTS profile_delete_sessions AFTER DELETE ON profiles
+BEGIN
+    DELETE FROM play_sessions WHERE profile_id = OLD.id;
+END;
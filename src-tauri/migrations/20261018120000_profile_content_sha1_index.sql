@@ -0,0 +1,2 @@
+-- used to count the profiles that use a file in the content store
+CREATE INDEX IF NOT EXISTS profile_content_sha1 ON profile_content (sha1);
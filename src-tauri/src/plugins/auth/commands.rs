@@ -13,7 +13,7 @@ pub async fn logout<R: tauri::Runtime>(_app: tauri::AppHandle<R>) -> Result<()>
 
 #[tauri::command]
 pub async fn refresh(state: State<'_, AuthAppState>, token: String) -> Result<AuthResponse> {
-    let sl = state.lock().await;
+    let mut sl = state.lock().await;
     sl.refresh(&token).await
 }
 
@@ -30,7 +30,7 @@ pub async fn authenticate<R: tauri::Runtime>(
     }
 
     let mut sl = state.lock().await;
-    let url = sl.generate_url(scopes)?;
+    let url = sl.generate_url(scopes).await?;
 
     let window = WebviewWindowBuilder::new(&app, "login", WebviewUrl::External(url))
         .title("Login")
@@ -65,37 +65,66 @@ impl FragmentParams {
     }
 }
 
+pub type AuthClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
 pub struct AuthState {
     pub flow: Option<(CsrfToken, PkceCodeVerifier, Nonce)>,
-    pub client: CoreClient<
-        EndpointSet,
-        EndpointNotSet,
-        EndpointNotSet,
-        EndpointNotSet,
-        EndpointMaybeSet,
-        EndpointMaybeSet,
-    >,
+    /// loaded on first use so the launcher can start while offline
+    client: Option<AuthClient>,
 }
 
 impl AuthState {
-    pub fn new() -> Result<Self> {
+    pub fn new() -> Self {
+        Self {
+            flow: None,
+            client: None,
+        }
+    }
+
+    /// Get the client, fetching the provider metadata if it has not been loaded yet
+    async fn client(&mut self) -> Result<AuthClient> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+
         let client_id = ClientId::new(env!("VITE_CLIENT_ID").to_string());
-        let http_client = tauri_plugin_http::reqwest::blocking::ClientBuilder::new().build()?;
-        let provider: CoreProviderMetadata = http_client.get(AUTHORITY).send()?.json()?;
+        let http_client = ClientBuilder::new().build()?;
+        let provider = http_client
+            .get(AUTHORITY)
+            .send()
+            .await?
+            .json::<CoreProviderMetadata>()
+            .await?;
 
-        let jwks: JsonWebKeySet<CoreJsonWebKey> = http_client
+        let jwks = http_client
             .get(provider.jwks_uri().to_string())
-            .send()?
-            .json()?;
+            .send()
+            .await?
+            .json::<JsonWebKeySet<CoreJsonWebKey>>()
+            .await?;
         let provider = provider.set_jwks(jwks);
 
         let client = CoreClient::from_provider_metadata(provider, client_id, None)
             .set_redirect_uri(RedirectUrl::new(CALLBACK_URI.to_string())?);
 
-        Ok(Self { flow: None, client })
+        self.client = Some(client.clone());
+
+        Ok(client)
     }
 
-    fn is_code_valid(&self, raw_id_token: &str, raw_code: String, nonce: &Nonce) -> Result<bool> {
+    fn is_code_valid(
+        client: &AuthClient,
+        raw_id_token: &str,
+        raw_code: String,
+        nonce: &Nonce,
+    ) -> Result<bool> {
         let id_token: IdToken<
             openidconnect::EmptyAdditionalClaims,
             CoreGenderClaim,
@@ -104,7 +133,7 @@ impl AuthState {
         > = IdToken::from_str(raw_id_token)?;
         let code = AuthorizationCode::new(raw_code);
 
-        let id_token_verifer = self.client.id_token_verifier();
+        let id_token_verifer = client.id_token_verifier();
         let claims = id_token.claims(&id_token_verifer, nonce)?;
         if let Some(c_hash) = claims.code_hash() {
             let hash = AuthorizationCodeHash::from_code(
@@ -119,11 +148,11 @@ impl AuthState {
         Ok(false)
     }
     /// generate login url for hybird flow
-    pub fn generate_url(&mut self, scopes: Vec<Scope>) -> Result<Url> {
+    pub async fn generate_url(&mut self, scopes: Vec<Scope>) -> Result<Url> {
+        let client = self.client().await?;
         let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
 
-        let (auth_url, csrf_token, nonce) = self
-            .client
+        let (auth_url, csrf_token, nonce) = client
             .authorize_url(
                 CoreAuthenticationFlow::Hybrid(vec![
                     CoreResponseType::Code,
@@ -169,6 +198,7 @@ impl AuthState {
             .get_param("state")
             .ok_or_else(|| Error::Reason("Failed to get param 3".to_string()))?;
 
+        let client = self.client().await?;
         let http_client = ClientBuilder::new().redirect(Policy::none()).build()?;
         let (crsf, pkce_verifier, nonce) = self
             .flow
@@ -178,22 +208,21 @@ impl AuthState {
         if crsf.secret() != state {
             return Err(Error::Reason("Invalid state".to_string()));
         }
-        if !self.is_code_valid(id_token, code.clone(), &nonce)? {
+        if !Self::is_code_valid(&client, id_token, code.clone(), &nonce)? {
             return Err(Error::Reason("Invalid code".to_string()));
         }
 
-        let token_url = self
-            .client
+        let token_url = client
             .token_uri()
             .ok_or_else(|| Error::Reason("Failed to get token url".to_string()))?;
 
         let mut params = HashMap::new();
         params.insert("code", code.to_owned());
         params.insert("scope", SCOPES_SUBSET.to_string());
-        params.insert("client_id", self.client.client_id().to_string());
+        params.insert("client_id", client.client_id().to_string());
         params.insert(
             "redirect_uri",
-            self.client
+            client
                 .redirect_uri()
                 .ok_or_else(|| Error::Reason("Failed to get redirect".to_string()))?
                 .to_string(),
@@ -223,9 +252,9 @@ impl AuthState {
         Ok(data)
     }
 
-    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse> {
-        let token_url = self
-            .client
+    pub async fn refresh(&mut self, refresh_token: &str) -> Result<AuthResponse> {
+        let client = self.client().await?;
+        let token_url = client
             .token_uri()
             .ok_or_else(|| Error::Reason("Missing token url".to_string()))?
             .to_string();
@@ -234,7 +263,7 @@ impl AuthState {
 
         let mut params = HashMap::new();
 
-        params.insert("client_id", self.client.client_id().as_str());
+        params.insert("client_id", client.client_id().as_str());
         params.insert("scope", SCOPES_SUBSET);
         params.insert("grant_type", "refresh_token");
         params.insert("refresh_type", "refresh_token");
@@ -22,7 +22,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         .setup(|app, _api| {
             log::debug!("Setup <rmcl-auth> plugin");
 
-            let state = AuthState::new()?;
+            let state = AuthState::new();
             app.manage(Mutex::new(state));
             app.manage(Mutex::new(ModrinthLoginState::new()));
 
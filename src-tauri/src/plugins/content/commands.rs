@@ -2,7 +2,10 @@ use minecraft_launcher_lib::database::RwDatabase;
 use minecraft_launcher_lib::events::DownloadEvent;
 use minecraft_launcher_lib::installer::content::ContentType;
 use minecraft_launcher_lib::installer::content::file;
-use minecraft_launcher_lib::models::queue::QueueType;
+use minecraft_launcher_lib::installer::content::store::ContentStore;
+use minecraft_launcher_lib::installer::endpoints::Endpoints;
+use minecraft_launcher_lib::installer::http::HttpConfig;
+use minecraft_launcher_lib::models::queue::{QueueItem, QueueState, QueueType};
 use tokio::sync::RwLock;
 
 use std::path::PathBuf;
@@ -10,6 +13,7 @@ use tauri::State;
 use tauri::ipc::Channel;
 use tokio::sync::Mutex;
 
+use super::desktop::FailedInstalls;
 use crate::error::Error;
 /// function to register the download listener to the frontend
 #[tauri::command]
@@ -30,17 +34,16 @@ pub async fn delete_profile(
     db: tauri::State<'_, RwLock<minecraft_launcher_lib::database::Database>>,
     profile: String,
 ) -> Result<(), Error> {
-    let app_dir = db
-        .read()
-        .await
-        .get_setting_as_path("path.app")
-        .await?
-        .join("profiles")
-        .join(&profile);
+    let root = db.read().await.get_setting_as_path("path.app").await?;
+    let app_dir = root.join("profiles").join(&profile);
 
     if app_dir.exists() {
         tokio::fs::remove_dir_all(&app_dir).await?;
     }
+
+    // the content rows of the profile are removed with it
+    ContentStore::new(&root.join("runtime")).prune(&db).await?;
+
     Ok(())
 }
 
@@ -98,20 +101,15 @@ pub async fn uninstall_content(
         _ => return Err(Error::Reason("Can not uninstall content type".to_string())),
     };
 
-    let file_path = db
-        .read()
-        .await
-        .get_setting_as_path("path.app")
-        .await?
-        .join("profiles")
-        .join(profile)
-        .join(dir)
-        .join(filename);
+    let root = db.read().await.get_setting_as_path("path.app").await?;
+    let file_path = root.join("profiles").join(profile).join(dir).join(filename);
 
     if file_path.exists() && file_path.is_file() {
         tokio::fs::remove_file(&file_path).await?;
     }
 
+    ContentStore::new(&root.join("runtime")).prune(&db).await?;
+
     Ok(())
 }
 
@@ -168,3 +166,38 @@ pub async fn import_external(
 pub async fn get_system_ram() -> u64 {
     minecraft_launcher_lib::utils::get_ram_gb()
 }
+
+/// Suggested max memory in MB for a profile, from the system memory and its installed mods
+#[tauri::command]
+pub async fn suggest_profile_memory(
+    db: tauri::State<'_, RwDatabase>,
+    profile: String,
+) -> Result<u64, Error> {
+    Ok(minecraft_launcher_lib::launcher::jvm::suggest_max_memory(&profile, &db).await?)
+}
+
+/// Put a queue item that failed to download its files back in the queue, so it is installed again
+#[tauri::command]
+pub async fn retry_install(
+    db: tauri::State<'_, RwDatabase>,
+    failed: tauri::State<'_, FailedInstalls>,
+    id: String,
+) -> Result<(), Error> {
+    if failed.lock().await.remove(&id).is_none() {
+        return Err(Error::Reason(format!(
+            "Queue item {} has no failed downloads",
+            id
+        )));
+    }
+
+    QueueItem::set_state(&id, QueueState::Pending, &db).await?;
+    Ok(())
+}
+
+/// Apply the network settings after they were saved
+#[tauri::command]
+pub async fn reload_network_settings(db: tauri::State<'_, RwDatabase>) -> Result<(), Error> {
+    HttpConfig::configure_from_settings(&db).await?;
+    Endpoints::configure_from_settings(&db).await?;
+    Ok(())
+}
@@ -5,16 +5,35 @@ use minecraft_launcher_lib::{
     installer::{
         InstallConfig,
         content::{self, InstallContent, UpdateContent, curseforge::install_curseforge_modpack},
+        endpoints::Endpoints,
+        http::HttpConfig,
         minecraft::install_minecraft,
+        utils::{DownloadError, DownloadScheduler},
     },
     models::{
         profile::{Profile, ProfileState},
         queue::{QueueItem, QueueState, QueueType},
     },
+    offline,
 };
-use std::time::Duration;
+use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
 use tauri::{AppHandle, Emitter, Manager, Runtime, ipc::Channel};
 
+/// Files that could not be downloaded by id of the queue item that failed.
+/// An item in this list can be installed again with `retry_install`.
+pub type FailedInstalls = tokio::sync::Mutex<HashMap<String, Vec<DownloadError>>>;
+
+/// Payload of `rmcl-content-install-failed`
+#[derive(Clone, Serialize)]
+struct InstallFailed {
+    /// id of the queue item
+    id: String,
+    message: String,
+    /// Files that could not be downloaded
+    downloads: Vec<DownloadError>,
+}
+
 async fn install_client(
     item: &QueueItem,
     db: &RwDatabase,
@@ -127,10 +146,69 @@ async fn install_update(
     }
 }
 
+/// Postpone the queue while offline and resume it once the launcher is back online.
+/// Returns `true` when the queue can be processed.
+async fn check_offline(
+    db: &RwDatabase,
+    emitter_state: &tokio::sync::Mutex<Option<Channel<DownloadEvent>>>,
+) -> bool {
+    if let Err(err) = offline::configure_from_settings(db).await {
+        log::error!("Failed to load offline mode: {}", err);
+    }
+
+    let is_offline = offline::is_offline() && !offline::check_connectivity().await;
+
+    let changed = if offline::went_online() {
+        QueueItem::resume_postponed(db).await
+    } else if is_offline {
+        QueueItem::postpone_pending(db).await
+    } else {
+        Ok(0)
+    };
+
+    match changed {
+        Ok(0) => {}
+        Ok(count) => {
+            log::info!(
+                "{} {} queue items",
+                if is_offline {
+                    "Offline: postponed"
+                } else {
+                    "Online: resumed"
+                },
+                count
+            );
+            if let Some(emitter) = emitter_state.lock().await.as_ref()
+                && let Err(err) = emitter.send(DownloadEvent::InvalidateQuery {
+                    query_key: vec!["DOWNLOAD_QUEUE".to_string()],
+                })
+            {
+                log::error!("{}", err)
+            }
+        }
+        Err(err) => log::error!("{}", err),
+    }
+
+    !is_offline
+}
+
 pub async fn install<R: Runtime>(app: &AppHandle<R>) {
     let db = app.state::<RwDatabase>();
     let emitter_state = app.state::<tokio::sync::Mutex<Option<Channel<DownloadEvent>>>>();
 
+    // the client is only rebuilt when the proxy, certificate or timeout settings changed
+    if let Err(err) = HttpConfig::configure_from_settings(&db).await {
+        log::error!("Failed to load http settings: {}", err);
+    }
+    // the connectivity probe has to use the mirror
+    if let Err(err) = Endpoints::configure_from_settings(&db).await {
+        log::error!("Failed to load download endpoints: {}", err);
+    }
+
+    if !check_offline(&db, &emitter_state).await {
+        return;
+    }
+
     let item = QueueItem::get_pending(&db).await;
 
     match item {
@@ -147,6 +225,11 @@ pub async fn install<R: Runtime>(app: &AppHandle<R>) {
                 return;
             }
 
+            // download limits may have been changed since the last item
+            if let Err(err) = DownloadScheduler::configure_from_settings(&db).await {
+                log::error!("Failed to load download limits: {}", err);
+            }
+
             let emitter = emitter_c.as_ref().unwrap();
             if let Err(err) = emitter.send(DownloadEvent::Init {
                 display_name: item.display_name.clone(),
@@ -173,11 +256,33 @@ pub async fn install<R: Runtime>(app: &AppHandle<R>) {
             let item_state = if let Err(err) = result {
                 log::error!("{}", err);
 
-                if let Err(err) = app.emit("rmcl-content-install-failed", err.to_string()) {
-                    log::error!("{}", err)
-                };
+                // connection was lost, the item is retried once the launcher is online again
+                if !offline::check_connectivity().await {
+                    QueueState::Postponed
+                } else {
+                    let downloads = match &err {
+                        Error::Lib(err) => err.failed_downloads().to_vec(),
+                        _ => Vec::new(),
+                    };
+                    if !downloads.is_empty() {
+                        app.state::<FailedInstalls>()
+                            .lock()
+                            .await
+                            .insert(item.id.clone(), downloads.clone());
+                    }
+                    if let Err(err) = app.emit(
+                        "rmcl-content-install-failed",
+                        InstallFailed {
+                            id: item.id.clone(),
+                            message: err.to_string(),
+                            downloads,
+                        },
+                    ) {
+                        log::error!("{}", err)
+                    };
 
-                QueueState::Errored
+                    QueueState::Errored
+                }
             } else {
                 QueueState::Completed
             };
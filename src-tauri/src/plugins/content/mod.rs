@@ -1,7 +1,10 @@
 mod commands;
 mod desktop;
 use minecraft_launcher_lib::{
-    database::RwDatabase, events::DownloadEvent, models::setting::Setting,
+    database::RwDatabase,
+    events::DownloadEvent,
+    installer::{endpoints::Endpoints, http::HttpConfig},
+    models::setting::Setting,
 };
 use std::time::Duration;
 use tauri::{
@@ -20,6 +23,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::<R>::new("rmcl-content")
         .setup(|app, _api| {
             app.manage(Mutex::new(Option::<Channel<DownloadEvent>>::None));
+            app.manage(desktop::FailedInstalls::default());
 
             // insure that a path.app setting has been set.
             if let Err(error) = tauri::async_runtime::block_on(async {
@@ -31,6 +35,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                     Setting::insert("path.app", str_path, None, &db).await?;
                 }
 
+                // the version lists are fetched before the first queue item is installed
+                if let Err(err) = HttpConfig::configure_from_settings(&db).await {
+                    log::error!("Failed to load http settings: {}", err);
+                }
+                if let Err(err) = Endpoints::configure_from_settings(&db).await {
+                    log::error!("Failed to load download endpoints: {}", err);
+                }
+
                 Ok::<_, Error>(())
             }) {
                 return Err(error.into());
@@ -74,6 +86,9 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::import_external,
             commands::downloads_listener,
             commands::get_system_ram,
+            commands::suggest_profile_memory,
+            commands::retry_install,
+            commands::reload_network_settings,
         ])
         .build()
 }
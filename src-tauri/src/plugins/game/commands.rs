@@ -1,8 +1,24 @@
 use super::PROCESSES_STATE_EVENT;
 use super::desktop::{PluginGameState, ProcessStatePayload};
 use minecraft_launcher_lib::database::RwDatabase;
-use minecraft_launcher_lib::launcher::{LaunchConfig, start_game};
+use minecraft_launcher_lib::installer::metadata::{
+    LoaderMetaVersion, VersionFilter, VersionManifest, get_loader_game_versions,
+    get_loader_versions, get_version_manifest,
+};
+use minecraft_launcher_lib::installer::runtimes::{
+    InstalledRuntime, check_runtime_update, list_runtimes, remove_unused_runtimes,
+    uninstall_runtime, update_runtime,
+};
+use minecraft_launcher_lib::java::discovery::{JavaInstall, discover_java};
+use minecraft_launcher_lib::launcher::{LaunchConfig, LaunchDryRun, dry_run, start_game};
+use minecraft_launcher_lib::launcher::ready::ReadySignal;
+use minecraft_launcher_lib::models::profile::Loader;
+use minecraft_launcher_lib::offline;
+use minecraft_launcher_lib::process::{LaunchStatus, OutputLine};
+use std::path::PathBuf;
+use tauri::ipc::Channel;
 use tauri::{Emitter, Runtime};
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::error::Error;
 use crate::plugins::game::desktop::{PROCESS_CRASH_EVENT, ProcessCrashEvent};
@@ -13,12 +29,12 @@ pub async fn launch_game<R: Runtime>(
     db: tauri::State<'_, RwDatabase>,
     ps: tauri::State<'_, PluginGameState>,
     config: LaunchConfig,
-) -> Result<(), Error> {
+) -> Result<Option<ReadySignal>, Error> {
     let profile_id = config.get_profile();
 
     log::debug!("Starting processes: Profile UUID: {}", profile_id);
 
-    let (tx, rx) = tokio::sync::oneshot::channel::<String>();
+    let (tx, rx) = tokio::sync::oneshot::channel::<LaunchStatus>();
 
     if let Err(err) = start_game(&db, &ps.0, config, tx).await.map_err(Error::Lib) {
         log::error!("{}", err);
@@ -33,28 +49,148 @@ pub async fn launch_game<R: Runtime>(
     }
 
     match rx.await {
-        Ok(result) => {
-            if result.as_str() == "process::exited" {
-                return Err(Error::Reason("Process was stopped".to_string()));
+        Ok(LaunchStatus::Ready(signal)) => {
+            log::debug!("Game started, detected by {:?}", signal);
+            return Ok(Some(signal));
+        }
+        Ok(LaunchStatus::Exited) => {
+            return Err(Error::Reason("Process was stopped".to_string()));
+        }
+        Ok(LaunchStatus::Timeout) => {
+            if let Err(err) = app.emit(
+                PROCESS_CRASH_EVENT,
+                ProcessCrashEvent::new(profile_id, "Launch timeout".to_string(), 1),
+            ) {
+                log::error!("{}", err);
             }
 
-            if result.as_str() == "error::timeout" {
-                if let Err(err) = app.emit(
-                    PROCESS_CRASH_EVENT,
-                    ProcessCrashEvent::new(profile_id, "Launch timeout".to_string(), 1),
-                ) {
-                    log::error!("{}", err);
-                }
-
-                return Err(Error::Reason("Game luanch timed out!".to_string()));
-            }
+            return Err(Error::Reason("Game luanch timed out!".to_string()));
+        }
+        Ok(LaunchStatus::IoError) => {
+            log::error!("Failed to watch game startup");
         }
         Err(err) => {
             log::error!("{}", err)
         }
     }
 
-    Ok(())
+    Ok(None)
+}
+
+/// Resolve the launch command of a profile without starting the game.
+#[tauri::command]
+pub async fn launch_dry_run(
+    db: tauri::State<'_, RwDatabase>,
+    config: LaunchConfig,
+) -> Result<LaunchDryRun, Error> {
+    dry_run(&db, config).await.map_err(Error::Lib)
+}
+
+/// Find the java installations that are already on the system.
+#[tauri::command]
+pub async fn find_java() -> Result<Vec<JavaInstall>, Error> {
+    discover_java().await.map_err(Error::Lib)
+}
+
+/// List the minecraft versions that match the filter.
+/// The version manifest is cached so this also works while offline.
+#[tauri::command]
+pub async fn list_minecraft_versions(
+    db: tauri::State<'_, RwDatabase>,
+    filter: VersionFilter,
+    refresh: Option<bool>,
+) -> Result<VersionManifest, Error> {
+    offline::configure_from_settings(&db)
+        .await
+        .map_err(Error::Lib)?;
+    let manifest = get_version_manifest(refresh.unwrap_or_default())
+        .await
+        .map_err(Error::Lib)?;
+
+    Ok(manifest.filtered(&filter))
+}
+
+/// List the loader versions of fabric or quilt, cached like the version manifest.
+#[tauri::command]
+pub async fn list_loader_versions(
+    db: tauri::State<'_, RwDatabase>,
+    loader: Loader,
+) -> Result<Vec<LoaderMetaVersion>, Error> {
+    offline::configure_from_settings(&db)
+        .await
+        .map_err(Error::Lib)?;
+    get_loader_versions(loader).await.map_err(Error::Lib)
+}
+
+/// List the minecraft versions that fabric or quilt can be installed for, cached like the version manifest.
+#[tauri::command]
+pub async fn list_loader_game_versions(
+    db: tauri::State<'_, RwDatabase>,
+    loader: Loader,
+) -> Result<Vec<LoaderMetaVersion>, Error> {
+    offline::configure_from_settings(&db)
+        .await
+        .map_err(Error::Lib)?;
+    get_loader_game_versions(loader).await.map_err(Error::Lib)
+}
+
+/// List the java runtimes installed by the launcher.
+#[tauri::command]
+pub async fn list_java_runtimes(
+    db: tauri::State<'_, RwDatabase>,
+) -> Result<Vec<InstalledRuntime>, Error> {
+    list_runtimes(&db).await.map_err(Error::Lib)
+}
+
+/// Get the newer build of a java runtime if there is one.
+#[tauri::command]
+pub async fn check_java_runtime_update(
+    db: tauri::State<'_, RwDatabase>,
+    name: String,
+) -> Result<Option<String>, Error> {
+    check_runtime_update(&name, &db).await.map_err(Error::Lib)
+}
+
+#[tauri::command]
+pub async fn update_java_runtime(
+    db: tauri::State<'_, RwDatabase>,
+    name: String,
+) -> Result<(), Error> {
+    update_runtime(&name, &db).await.map_err(Error::Lib)
+}
+
+#[tauri::command]
+pub async fn uninstall_java_runtime(
+    db: tauri::State<'_, RwDatabase>,
+    ps: tauri::State<'_, PluginGameState>,
+    name: String,
+) -> Result<(), Error> {
+    let running = running_executables(&ps).await;
+    uninstall_runtime(&name, &running, &db)
+        .await
+        .map_err(Error::Lib)
+}
+
+/// Remove the java runtimes that no profile needs.
+#[tauri::command]
+pub async fn remove_unused_java_runtimes(
+    db: tauri::State<'_, RwDatabase>,
+    ps: tauri::State<'_, PluginGameState>,
+) -> Result<Vec<String>, Error> {
+    let running = running_executables(&ps).await;
+    remove_unused_runtimes(&running, &db)
+        .await
+        .map_err(Error::Lib)
+}
+
+/// Executables of the running games
+async fn running_executables(ps: &PluginGameState) -> Vec<PathBuf> {
+    ps.0.read()
+        .await
+        .state
+        .values()
+        .map(|process| PathBuf::from(&process.exe))
+        .collect()
 }
 
 #[tauri::command]
@@ -78,3 +214,48 @@ pub async fn list_active_processes(
 
     Ok(ProcessStatePayload::List(data.clone()))
 }
+
+/// Stream the stdout and stderr of a running game.
+/// The buffered lines are sent first then any new lines as they are written.
+#[tauri::command]
+pub async fn stream_process_output(
+    ps: tauri::State<'_, PluginGameState>,
+    profile_id: String,
+    on_event: Channel<OutputLine>,
+) -> Result<(), Error> {
+    let (history, mut rx) = {
+        let state = ps.0.read().await;
+        let process = state
+            .get_by_profile(&profile_id)
+            .ok_or_else(|| Error::Reason("No process is running for profile".to_string()))?;
+        let output = process
+            .output
+            .as_ref()
+            .ok_or_else(|| Error::Reason("Process output is not available".to_string()))?;
+        output.subscribe()
+    };
+
+    tauri::async_runtime::spawn(async move {
+        for line in history {
+            if on_event.send(line).is_err() {
+                return;
+            }
+        }
+
+        loop {
+            match rx.recv().await {
+                Ok(line) => {
+                    if on_event.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("Process output stream skipped {} lines", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    Ok(())
+}
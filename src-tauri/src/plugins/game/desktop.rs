@@ -1,4 +1,6 @@
 use minecraft_launcher_lib::database::RwDatabase;
+use minecraft_launcher_lib::launcher::crash::CrashReport;
+use minecraft_launcher_lib::models::session::PlaySession;
 use minecraft_launcher_lib::process::Processes;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, Runtime};
@@ -26,6 +28,7 @@ pub struct ProcessCrashEvent {
     profile: String,
     code: i32,
     details: String,
+    report: Option<CrashReport>,
 }
 
 impl ProcessCrashEvent {
@@ -34,6 +37,21 @@ impl ProcessCrashEvent {
             profile,
             code,
             details,
+            report: None,
+        }
+    }
+
+    pub fn with_report(profile: String, code: i32, report: Option<CrashReport>) -> Self {
+        let details = report
+            .as_ref()
+            .and_then(|report| report.summary.clone())
+            .unwrap_or_default();
+
+        Self {
+            profile,
+            code,
+            details,
+            report,
         }
     }
 }
@@ -41,10 +59,11 @@ impl ProcessCrashEvent {
 pub async fn process_watcher<R: Runtime>(app: &AppHandle<R>) {
     let state = app.state::<PluginGameState>();
 
-    let removable = {
+    let (removable, crashed) = {
         let mut ps_list = state.0.write().await;
 
         let mut removable = Vec::new();
+        let mut crashed = Vec::new();
         for (uuid, ps) in &mut ps_list.state {
             let profile_id = ps.profile_id.clone();
 
@@ -58,27 +77,61 @@ pub async fn process_watcher<R: Runtime>(app: &AppHandle<R>) {
                 // no exit code
                 e if e < 0 => continue,
                 // error exit code
+                e if e > 0 && ps.was_killed() => {
+                    log::debug!("Process was stopped: {}", uuid);
+                    removable.push((uuid.clone(), profile_id, e));
+                }
                 e if e > 0 => {
                     log::debug!("Process crashed: {}", uuid);
-                    removable.push((uuid.clone(), profile_id));
+
+                    // the crash report is read after the lock is released
+                    crashed.push((profile_id.clone(), e, ps.crash_context()));
+                    removable.push((uuid.clone(), profile_id, e));
                 }
                 // 0 exit code
-                _ => {
+                e => {
                     log::debug!("Process exited: {}", uuid);
-                    removable.push((uuid.clone(), profile_id));
+                    removable.push((uuid.clone(), profile_id, e));
                 }
             }
         }
-        removable
+        (removable, crashed)
     };
 
+    for (profile_id, code, context) in crashed {
+        let report = match context {
+            Some(context) => match context.analyze().await {
+                Ok(report) => Some(report),
+                Err(err) => {
+                    log::error!("Failed to read crash report: {}", err);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let event = ProcessCrashEvent::with_report(profile_id, code, report);
+        if let Err(err) = app.emit(PROCESS_CRASH_EVENT, event) {
+            log::error!("{}", err);
+        }
+    }
+
     // drop old processes
     if !removable.is_empty() {
         let db = app.state::<RwDatabase>();
 
         log::debug!("Removing old processes: {:?}", removable);
 
-        let (process_ids, profile_ids) = removable.into_iter().unzip();
+        for (uuid, _, code) in &removable {
+            if let Err(err) = PlaySession::end(uuid, Some(*code), &db).await {
+                log::error!("Failed to end play session: {}", err);
+            }
+        }
+
+        let (process_ids, profile_ids): (Vec<String>, Vec<String>) = removable
+            .into_iter()
+            .map(|(uuid, profile_id, _)| (uuid, profile_id))
+            .unzip();
 
         let mut state = state.0.write().await;
         if let Err(err) = state.remove_from_cache(&db, &process_ids).await {
@@ -10,7 +10,9 @@ use tauri::{
 };
 use tokio_util::sync::CancellationToken;
 
-use minecraft_launcher_lib::{database::RwDatabase, process::Processes};
+use minecraft_launcher_lib::{
+    database::RwDatabase, models::session::PlaySession, process::Processes,
+};
 use tokio::select;
 
 use crate::error::Error;
@@ -27,6 +29,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                 let db = app.state::<RwDatabase>();
                 data.load_cache(&db).await.map_err(Error::Lib)?;
 
+                // sessions of games that exited while the launcher was closed
+                let running = data.state.keys().cloned().collect::<Vec<String>>();
+                match PlaySession::close_dangling(&running, &db).await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("Closed {} dangling play sessions", count),
+                    Err(err) => log::error!("Failed to close play sessions: {}", err),
+                }
+
                 Ok::<Processes, Error>(data)
             })?;
 
@@ -70,8 +80,19 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         })
         .invoke_handler(tauri::generate_handler![
             commands::launch_game,
+            commands::launch_dry_run,
+            commands::find_java,
+            commands::list_minecraft_versions,
+            commands::list_loader_versions,
+            commands::list_loader_game_versions,
+            commands::list_java_runtimes,
+            commands::check_java_runtime_update,
+            commands::update_java_runtime,
+            commands::uninstall_java_runtime,
+            commands::remove_unused_java_runtimes,
             commands::stop,
-            commands::list_active_processes
+            commands::list_active_processes,
+            commands::stream_process_output
         ])
         .build()
 }
@@ -1,6 +1,6 @@
 import { Suspense, useCallback, useRef, useState } from "react";
-import type { ControllerRenderProps } from "react-hook-form";
-import update, { type Spec } from "immutability-helper";
+import type { Control } from "react-hook-form";
+import { useQuery } from "@tanstack/react-query";
 import { Plus } from "lucide-react";
 
 import {
@@ -12,98 +12,233 @@ import {
 	DialogTitle,
 	DialogTrigger,
 } from "./ui/dialog";
+import {
+	Select,
+	SelectContent,
+	SelectItem,
+	SelectTrigger,
+	SelectValue,
+} from "./ui/select";
+import {
+	FormControl,
+	FormDescription,
+	FormField,
+	FormItem,
+	FormLabel,
+	FormMessage,
+} from "./ui/form";
 import type { Profile } from "@/lib/models/profiles";
-import { parseJVMArgs, argsToString } from "@/lib/JvmArgs";
+import { suggestProfileMemory } from "@/lib/api/plugins/content";
+import { splitArgs, joinArgs } from "@/lib/JvmArgs";
 import { MemorySlider } from "./ui/memory-slider";
 import { Separator } from "./ui/separator";
 import { Button } from "./ui/button";
-import { Label } from "./ui/label";
+import { Switch } from "./ui/switch";
 import { Badge } from "./ui/badge";
 import { Input } from "./ui/input";
 
+const MB_IN_GB = 1024;
+
+/**
+ * Memory, garbage collector and extra jvm args of a profile.
+ * @param profileId used to show the suggested memory of a existing profile
+ */
 export const JVMArgForm: React.FC<{
-	controller: ControllerRenderProps<Profile>;
-}> = ({ controller: { value, onChange } }) => {
+	control: Control<Profile>;
+	profileId?: string;
+}> = ({ control, profileId }) => {
 	const ref = useRef<HTMLDivElement>(null);
 	const [showDialog, setShowDialog] = useState(false);
 	const [argValue, setArgValue] = useState("");
-	const [state, setState] = useState(() => parseJVMArgs(value));
 
-	const change = useCallback(
-		(s: ReturnType<typeof parseJVMArgs>, action: Spec<{ memory: number[]; args: string[]; }, never>) => {
-			const newData = update(s, action);
-			setState(newData);
-			onChange(argsToString(newData));
-			ref.current?.dispatchEvent(new Event("change", { bubbles: true }));
-		},
-		[onChange],
-	);
+	const suggested = useQuery({
+		queryKey: ["SUGGESTED_MEMORY", profileId],
+		queryFn: () => suggestProfileMemory(profileId as string),
+		enabled: !!profileId,
+	});
+
+	// the profile edit page saves on native change events
+	const changed = useCallback(() => {
+		ref.current?.dispatchEvent(new Event("change", { bubbles: true }));
+	}, []);
 
 	return (
 		<div className="space-y-4" ref={ref}>
 			<Separator />
-			<div className="flex flex-col gap-4 items-center mb-8">
-				<Label className="text-left w-full">Max Memory</Label>
-
-				<Suspense>
-					<MemorySlider value={state.memory[0]} onChange={(v) => change(state, { memory: { $set: [v] } })} />
-				</Suspense>
-			</div>
+			<FormField
+				control={control}
+				name="max_memory"
+				render={({ field }) => (
+					<FormItem>
+						<div className="flex justify-between items-center">
+							<FormLabel>Max Memory</FormLabel>
+							<div className="flex items-center gap-2">
+								<span className="text-sm">Automatic</span>
+								<Switch
+									checked={field.value === null}
+									onCheckedChange={(auto) => {
+										field.onChange(
+											auto ? null : (suggested.data ?? 4 * MB_IN_GB),
+										);
+										changed();
+									}}
+								/>
+							</div>
+						</div>
+						<FormControl>
+							{field.value === null ? (
+								<p className="text-sm">
+									{suggested.data
+										? `${(suggested.data / MB_IN_GB).toFixed(1)}GB, suggested from the system memory and installed mods.`
+										: "Suggested from the system memory and installed mods when the game is started."}
+								</p>
+							) : (
+								<Suspense>
+									<MemorySlider
+										value={Math.round((field.value as number) / MB_IN_GB)}
+										onChange={(value) => {
+											field.onChange(value * MB_IN_GB);
+											changed();
+										}}
+									/>
+								</Suspense>
+							)}
+						</FormControl>
+						<FormMessage />
+					</FormItem>
+				)}
+			/>
 
-			<div className="space-y-4 mt-4">
-				<div className="flex justify-between items-center">
-					<Label>Other Jvm Args</Label>
-					<Dialog onOpenChange={setShowDialog} open={showDialog}>
-						<DialogTrigger asChild>
-							<Button type="button" size="sm">
-								{" "}
-								<Plus className="h-4 w-4" /> Add
-							</Button>
-						</DialogTrigger>
-						<DialogContent>
-							<DialogHeader>
-								<DialogTitle>Add Arg</DialogTitle>
-								<DialogDescription>
-									New argument to pass to the jvm
-								</DialogDescription>
-							</DialogHeader>
+			<FormField
+				control={control}
+				name="min_memory"
+				render={({ field }) => (
+					<FormItem className="flex flex-row items-center justify-between">
+						<div className="space-y-0.5">
+							<FormLabel>Min Memory (MB)</FormLabel>
+							<FormDescription>
+								Heap the game starts with, leave empty for the java default.
+							</FormDescription>
+						</div>
+						<FormControl>
 							<Input
-								value={argValue}
-								onChange={(e) => setArgValue(e.target.value)}
-								placeholder="-XX:+UseG1GC"
+								className="w-32"
+								type="number"
+								min={0}
+								value={field.value ?? ""}
+								onChange={(ev) =>
+									field.onChange(
+										ev.target.value.length
+											? Number.parseInt(ev.target.value)
+											: null,
+									)
+								}
+								placeholder="512"
 							/>
-							<DialogFooter>
-								<Button
-									type="button"
-									onClick={() => {
-										change(state, { args: { $push: [argValue] } })
-										setArgValue("");
-										setShowDialog(false);
-									}}
-								>
-									Ok
-								</Button>
-							</DialogFooter>
-						</DialogContent>
-					</Dialog>
-				</div>
+						</FormControl>
+					</FormItem>
+				)}
+			/>
 
-				<div className="flex flex-wrap gap-2 bg-input rounded-md shadow p-4">
-					{state.args.map((e, i) => (
-						<Badge
-							onClick={() => {
-								const idx = state.args.indexOf(e);
-								if (idx === -1) return;
-								change(state, { args: { $splice: [[idx, 1]] } });
+			<FormField
+				control={control}
+				name="gc_preset"
+				render={({ field }) => (
+					<FormItem className="flex flex-row items-center justify-between">
+						<div className="space-y-0.5">
+							<FormLabel>Garbage Collector</FormLabel>
+							<FormDescription>
+								ZGC needs java 15 or newer.
+							</FormDescription>
+						</div>
+						<Select
+							value={field.value ?? "default"}
+							onValueChange={(value) => {
+								field.onChange(value);
+								changed();
 							}}
-							className="select-none cursor-alias"
-							key={`${i + 1}`}
 						>
-							{e}
-						</Badge>
-					))}
-				</div>
-			</div>
+							<FormControl>
+								<SelectTrigger className="w-32">
+									<SelectValue />
+								</SelectTrigger>
+							</FormControl>
+							<SelectContent>
+								<SelectItem value="default">Java Default</SelectItem>
+								<SelectItem value="g1">G1</SelectItem>
+								<SelectItem value="zgc">ZGC</SelectItem>
+							</SelectContent>
+						</Select>
+					</FormItem>
+				)}
+			/>
+
+			<FormField
+				control={control}
+				name="java_args"
+				render={({ field }) => {
+					const args = splitArgs(field.value) ?? [];
+					const setArgs = (value: string[]) => {
+						field.onChange(value.length ? joinArgs(value) : null);
+						changed();
+					};
+
+					return (
+						<FormItem className="space-y-4 mt-4">
+							<div className="flex justify-between items-center">
+								<FormLabel>Other Jvm Args</FormLabel>
+								<Dialog onOpenChange={setShowDialog} open={showDialog}>
+									<DialogTrigger asChild>
+										<Button type="button" size="sm">
+											{" "}
+											<Plus className="h-4 w-4" /> Add
+										</Button>
+									</DialogTrigger>
+									<DialogContent>
+										<DialogHeader>
+											<DialogTitle>Add Arg</DialogTitle>
+											<DialogDescription>
+												New argument to pass to the jvm
+											</DialogDescription>
+										</DialogHeader>
+										<Input
+											value={argValue}
+											onChange={(e) => setArgValue(e.target.value)}
+											placeholder="-Dfoo=bar"
+										/>
+										<DialogFooter>
+											<Button
+												type="button"
+												disabled={!argValue.length}
+												onClick={() => {
+													setArgs([...args, argValue]);
+													setArgValue("");
+													setShowDialog(false);
+												}}
+											>
+												Ok
+											</Button>
+										</DialogFooter>
+									</DialogContent>
+								</Dialog>
+							</div>
+
+							<div className="flex flex-wrap gap-2 bg-input rounded-md shadow p-4">
+								{args.map((e, i) => (
+									<Badge
+										onClick={() => setArgs(args.filter((_, idx) => idx !== i))}
+										className="select-none cursor-alias"
+										key={`${i + 1}`}
+									>
+										{e}
+									</Badge>
+								))}
+							</div>
+							<FormMessage />
+						</FormItem>
+					);
+				}}
+			/>
 		</div>
 	);
 };
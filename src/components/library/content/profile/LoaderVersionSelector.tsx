@@ -25,17 +25,10 @@ import {
 	PopoverTrigger,
 } from "@/components/ui/popover";
 import type { Profile } from "@/lib/models/profiles";
+import { listLoaderVersions } from "@/lib/api/plugins/game";
 import { Button } from "@/components/ui/button";
 import { cn } from "@/lib/utils";
 
-type FabricMetadata = {
-	version: string;
-	stable: boolean;
-};
-type QuiltMetadata = {
-	version: string;
-};
-
 export const LoaderVersionSelector: React.FC<{
 	form: UseFormReturn<Profile>;
 	stable: boolean;
@@ -50,20 +43,14 @@ export const LoaderVersionSelector: React.FC<{
 		queryFn: async () => {
 			switch (loader) {
 				case "fabric": {
-					const response = await fetch(
-						"https://meta.fabricmc.net/v2/versions/loader",
-					);
-					const data = (await response.json()) as FabricMetadata[];
+					const data = await listLoaderVersions("fabric");
 					if (!stable) {
 						return data.map((item) => item.version);
 					}
 					return data.filter((item) => item.stable).map((item) => item.version);
 				}
 				case "quilt": {
-					const response = await fetch(
-						"https://meta.quiltmc.org/v3/versions/loader",
-					);
-					const data = (await response.json()) as QuiltMetadata[];
+					const data = await listLoaderVersions("quilt");
 					if (!stable) {
 						return data.map((item) => item.version);
 					}
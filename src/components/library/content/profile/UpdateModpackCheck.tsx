@@ -111,6 +111,8 @@ export const UpdateModpackCheck: React.FC<{
 											files: [
 												{
 													sha1: file?.hashes.sha1,
+													sha512: file?.hashes.sha512,
+													size: file?.size,
 													url: file?.url,
 													id: data.nextVersionData?.id,
 													filename: file?.filename,
@@ -7,6 +7,7 @@ export type ToastData = {
 	description?: string;
 	error?: string | Error;
 	showCloseBtn?: boolean;
+	action?: { label: string; onClick: () => void };
 };
 
 export const Toast: React.FC<ToastContentProps<ToastData>> = ({
@@ -17,6 +18,18 @@ export const Toast: React.FC<ToastContentProps<ToastData>> = ({
 		<div className="flex flex-col w-full">
 			<div className="text-sm font-semibold [&+div]:text-xs">{data.title}</div>
 			<div className="text-sm opacity-90">{data.description}</div>
+			{data.action ? (
+				<button
+					type="button"
+					className="mt-2 self-start rounded-md border px-3 py-1 text-xs font-medium hover:bg-secondary/20"
+					onClick={() => {
+						data.action?.onClick();
+						closeToast();
+					}}
+				>
+					{data.action.label}
+				</button>
+			) : null}
 			{showCloseBtn ? <ToastClose onClick={closeToast} /> : null}
 		</div>
 	);
@@ -1,5 +1,11 @@
 import { useQuery } from "@tanstack/react-query";
 
+import {
+	listLoaderGameVersions,
+	listLoaderVersions,
+	listMinecraftVersions,
+} from "@/lib/api/plugins/game";
+
 type VersionManifestV2 = {
 	latest: { release: string; snapshot: string };
 	versions: { id: string; type: string; releaseTime: string }[];
@@ -17,22 +23,8 @@ const useMinecraftVersions = (type: LoaderType = "vanilla") => {
 			switch (id) {
 				case "fabric": {
 					const [versions, loaders] = await Promise.all([
-						fetch("https://meta.fabricmc.net/v2/versions/game").then(
-							(value) =>
-								value.json() as Promise<{ version: string; stable: boolean }[]>,
-						),
-						fetch("https://meta.fabricmc.net/v2/versions/loader").then(
-							(value) =>
-								value.json() as Promise<
-									{
-										separator: string;
-										build: string;
-										maven: string;
-										version: string;
-										stable: boolean;
-									}[]
-								>,
-						),
+						listLoaderGameVersions("fabric"),
+						listLoaderVersions("fabric"),
 					]);
 
 					const loader = loaders.find((value) => value.stable);
@@ -57,9 +49,9 @@ const useMinecraftVersions = (type: LoaderType = "vanilla") => {
 					};
 				}
 				default: {
-					const version = await fetch(
-						"https://launchermeta.mojang.com/mc/game/version_manifest_v2.json",
-					).then((value) => value.json() as Promise<VersionManifestV2>);
+					const version = await listMinecraftVersions({
+						released_after: cutoff_date.toISOString(),
+					});
 					return {
 						latest: version.latest,
 						versions: [
@@ -69,11 +61,7 @@ const useMinecraftVersions = (type: LoaderType = "vanilla") => {
 								type: "latest-snapshot",
 								releaseTime: "",
 							},
-						].concat(
-							version.versions.filter(
-								(value) => new Date(value.releaseTime) >= cutoff_date,
-							),
-						),
+						].concat(version.versions),
 					};
 				}
 			}
@@ -1,36 +1,24 @@
 import { expect, test, describe } from "vitest";
-import { parseJVMArgs, argsToString } from "./JvmArgs";
+import { splitArgs, joinArgs } from "./JvmArgs";
 
-const TEST_ARGS = "-Xmx2G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M";
+const TEST_ARGS = `-XX:+UseStringDeduplication -Dfoo="a b" '-Dbar=c d' -Dbaz=it\\'s`;
 
 describe("JVM args parser", () => {
-    test("parseJVMArgs()", () => {
-        expect(parseJVMArgs(TEST_ARGS)).toMatchObject({
-            memory: [2],
-            args: [
-                "-XX:+UnlockExperimentalVMOptions",
-                "-XX:+UseG1GC",
-                "-XX:G1NewSizePercent=20",
-                "-XX:G1ReservePercent=20",
-                "-XX:MaxGCPauseMillis=50",
-                "-XX:G1HeapRegionSize=32M"
-            ]
-        })
+    test("splitArgs()", () => {
+        expect(splitArgs(TEST_ARGS)).toEqual([
+            "-XX:+UseStringDeduplication",
+            "-Dfoo=a b",
+            "-Dbar=c d",
+            "-Dbaz=it's",
+        ]);
+        expect(splitArgs(null)).toEqual([]);
+        expect(splitArgs(`-Dfoo="a b`)).toBeNull();
     });
 
-    test("argsToString()", () => {
+    test("joinArgs()", () => {
+        const args = ["-XX:+UseStringDeduplication", "-Dfoo=a b", "-Dbaz=it's"];
 
-        expect(argsToString({
-            memory: [2],
-            args: [
-                "-XX:+UnlockExperimentalVMOptions",
-                "-XX:+UseG1GC",
-                "-XX:G1NewSizePercent=20",
-                "-XX:G1ReservePercent=20",
-                "-XX:MaxGCPauseMillis=50",
-                "-XX:G1HeapRegionSize=32M"
-            ]
-
-        })).toBe(TEST_ARGS)
+        expect(joinArgs(args)).toBe(`-XX:+UseStringDeduplication '-Dfoo=a b' '-Dbaz=it'\\''s'`);
+        expect(splitArgs(joinArgs(args))).toEqual(args);
     });
 });
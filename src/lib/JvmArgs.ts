@@ -1,25 +1,68 @@
-// -Xmx2G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M
-export function parseJVMArgs(value: string | undefined | null) {
-	if (!value) return { memory: [2], args: [] };
-
-	const splitedArgs = value.split(" ");
-	const xmxIndex = splitedArgs.findIndex((e) => e.startsWith("-Xmx"));
-
-	let memory = 2;
-	if (xmxIndex !== -1) {
-		const arg = splitedArgs.splice(xmxIndex, 1).at(0);
-		if (arg) {
-			const target = arg.match(/-Xmx(?<value>\d+)(?<size>[KMGkmg])/);
-			if (target?.groups) {
-				memory = Number.parseInt(target.groups.value);
+// Java args are stored as one string and split with shell word rules, the same way the launcher splits them.
+// ex: -Dfoo="a b" -XX:+UseStringDeduplication
+
+/**
+ * Split a string of jvm args into its args.
+ * Single and double quotes keep spaces in a arg.
+ * @returns null when a quote is not closed
+ */
+export function splitArgs(value: string | undefined | null): string[] | null {
+	if (!value) return [];
+
+	const args: string[] = [];
+	let current: string | null = null;
+	let quote: "'" | '"' | null = null;
+
+	for (let i = 0; i < value.length; i++) {
+		const char = value[i];
+
+		if (quote === "'") {
+			if (char === "'") quote = null;
+			else current += char;
+			continue;
+		}
+
+		if (quote === '"') {
+			if (char === '"') {
+				quote = null;
+			} else if (char === "\\" && ['"', "\\", "$", "`"].includes(value[i + 1])) {
+				current += value[++i];
+			} else {
+				current += char;
 			}
+			continue;
+		}
+
+		if (/\s/.test(char)) {
+			if (current !== null) args.push(current);
+			current = null;
+			continue;
+		}
+
+		current ??= "";
+		if (char === "'" || char === '"') {
+			quote = char;
+		} else if (char === "\\" && i + 1 < value.length) {
+			current += value[++i];
+		} else {
+			current += char;
 		}
 	}
 
-	return { memory: [memory], args: splitedArgs };
+	if (quote) return null;
+	if (current !== null) args.push(current);
+
+	return args;
 }
 
-export function argsToString(value: { memory: number[]; args: string[] }) {
-	const output = [`-Xmx${value.memory[0]}G`, ...value.args];
-	return output.join(" ");
+/**
+ * Join jvm args into one string, args with spaces or quotes are quoted.
+ */
+export function joinArgs(args: string[]) {
+	return args
+		.map((arg) => {
+			if (arg.length && !/[\s'"\\$`]/.test(arg)) return arg;
+			return `'${arg.replaceAll("'", "'\\''")}'`;
+		})
+		.join(" ");
 }
@@ -36,6 +36,17 @@ export type DownloadEvent =
 			message?: string;
 		};
 	}
+	| {
+		event: "transfer";
+		data: {
+			bytes_done: number;
+			bytes_total: number;
+			files_done: number;
+			files_total: number;
+			bytes_per_second: number;
+			eta_seconds: number | null;
+		};
+	}
 	| { event: "finished"; data: unknown }
 	| { event: "refreshProfile" }
 	| { event: "toast", data: { status: string; message: string } }
@@ -54,6 +65,49 @@ export async function getSystemRam() {
 	return value;
 }
 
+/**
+ * Get the suggested max memory of a profile in MB
+ * @param profile id of the profile
+ */
+export async function suggestProfileMemory(profile: string) {
+	return invoke<number>("plugin:rmcl-content|suggest_profile_memory", {
+		profile,
+	});
+}
+
+/** A file that could not be downloaded while installing a queue item */
+export type DownloadError = {
+	url: string;
+	path: string;
+	attempts: number;
+	status: number | null;
+	hash_algorithm: "sha1" | "sha256" | "sha512" | null;
+	expected_hash: string | null;
+	actual_hash: string | null;
+	reason: string;
+};
+
+/** Payload of the `rmcl-content-install-failed` event */
+export type InstallFailed = {
+	/** id of the queue item */
+	id: string;
+	message: string;
+	downloads: DownloadError[];
+};
+
+/** Apply the network settings after they were saved */
+export async function reloadNetworkSettings() {
+	return invoke<void>("plugin:rmcl-content|reload_network_settings");
+}
+
+/**
+ * Put a queue item that failed to download its files back in the queue
+ * @param id queue item id from a `rmcl-content-install-failed` event
+ */
+export async function retryInstall(id: string) {
+	return invoke<void>("plugin:rmcl-content|retry_install", { id });
+}
+
 export async function uninstallContentById(
 	contentType: keyof typeof ContentType,
 	profileId: string,
@@ -95,7 +149,7 @@ export async function createProfile(
 		copy_from: copy,
 	});
 	await transaction((tx) => {
-		tx`INSERT INTO profiles (id,name,icon,date_created,version,loader,loader_version,java_args,resolution_width,resolution_height) VALUES (${args.id},${args.name},${args.icon},${args.date_created},${args.version},${args.loader},${args.loader_version},${args.java_args},${args.resolution_width},${args.resolution_height});`;
+		tx`INSERT INTO profiles (id,name,icon,date_created,version,loader,loader_version,java_args,min_memory,max_memory,gc_preset,resolution_width,resolution_height) VALUES (${args.id},${args.name},${args.icon},${args.date_created},${args.version},${args.loader},${args.loader_version},${args.java_args},${args.min_memory},${args.max_memory},${args.gc_preset},${args.resolution_width},${args.resolution_height});`;
 		tx`INSERT INTO download_queue (id,priority,display_name,profile_id,content_type,metadata) VALUES (${queueId},1,${`Minecraft ${args.loader} ${args.version}`},${args.id},${ContentType.Client},${JSON.stringify(
 			{
 				version: args.version,
@@ -121,7 +175,10 @@ export async function copyProfile(oldProfile: Profile, newProfile: string) {
 			${oldProfile.resolution_width},
 			${oldProfile.resolution_height},
 			${oldProfile.state},
-			${oldProfile.is_modpack});`;
+			${oldProfile.is_modpack},
+			${oldProfile.min_memory},
+			${oldProfile.max_memory},
+			${oldProfile.gc_preset});`;
 		tx`CREATE TEMPORARY TABLE copy_profile_row AS SELECT * FROM profile_content WHERE profile = ${oldProfile.id};`;
 		tx`UPDATE copy_profile_row SET profile = ${newProfile};`
 		tx`INSERT INTO profile_content SELECT * FROM copy_profile_row;`;
@@ -1,5 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
-import { query, sqlValue } from "./query";
+import { invoke, type Channel } from "@tauri-apps/api/core";
 
 export type LaunchConfig = {
 	auth_player_name: string;
@@ -7,11 +6,23 @@ export type LaunchConfig = {
 	auth_access_token: string;
 	auth_xuid: string;
 	profile_id: string;
+	/** name of the singleplayer world to load into */
+	quick_play_singleplayer?: string;
+	/** server address to join, ex: mc.example.com:25565 */
+	quick_play_multiplayer?: string;
+	/** id of the realm to join */
+	quick_play_realms?: string;
 };
+/** The signal that was used to detect that the game has started */
+export type ReadySignal =
+	| { type: "log_pattern"; data: string }
+	| { type: "window" }
+	| { type: "log_activity" }
+	| { type: "none" };
+
 export async function launchGame(config: LaunchConfig) {
 	try {
-		await query`UPDATE profiles SET last_played = '${sqlValue(new Date().toISOString())}' WHERE id = ${config.profile_id}`.run();
-		await invoke<void>("plugin:rmcl-game|launch_game", {
+		return await invoke<ReadySignal | null>("plugin:rmcl-game|launch_game", {
 			config,
 		});
 	} catch (error) {
@@ -19,6 +30,140 @@ export async function launchGame(config: LaunchConfig) {
 		throw new Error(error as string);
 	}
 }
+export type LaunchDryRun = {
+	java_exe: string;
+	java_exists: boolean;
+	working_directory: string;
+	classpath: { path: string; exists: boolean }[];
+	jvm_args: string[];
+	main_class: string;
+	game_args: string[];
+};
+
+/** Get the launch command of a profile without starting the game. The access token is redacted. */
+export async function launchDryRun(config: LaunchConfig) {
+	return invoke<LaunchDryRun>("plugin:rmcl-game|launch_dry_run", {
+		config,
+	});
+}
+
+export type JavaInstall = {
+	path: string;
+	major_version: number;
+	version: string;
+	vendor: string | null;
+	arch: string | null;
+	/** 32 or 64 bit */
+	data_model: number | null;
+	runtime_name: string | null;
+};
+
+export type VersionType =
+	| "release"
+	| "snapshot"
+	| "old_beta"
+	| "old_alpha"
+	| "unknown";
+export type MinecraftVersion = {
+	id: string;
+	type: VersionType;
+	url: string;
+	time: string;
+	releaseTime: string;
+	sha1: string;
+	complianceLevel: number;
+};
+export type VersionManifest = {
+	latest: { release: string; snapshot: string };
+	/** newest version first */
+	versions: MinecraftVersion[];
+};
+/** Filter of the version list, fields that are not set match every version. */
+export type VersionFilter = {
+	types?: VersionType[];
+	/** rfc3339 date */
+	released_after?: string;
+	/** rfc3339 date */
+	released_before?: string;
+};
+
+/** List the minecraft versions that match the filter. The version manifest is cached so this works while offline. */
+export async function listMinecraftVersions(
+	filter: VersionFilter = {},
+	refresh = false,
+) {
+	return invoke<VersionManifest>("plugin:rmcl-game|list_minecraft_versions", {
+		filter,
+		refresh,
+	});
+}
+
+/** A game or loader version of the fabric or quilt meta server */
+export type LoaderMetaVersion = {
+	version: string;
+	/** not set for quilt loader versions */
+	stable: boolean | null;
+};
+
+const metaLoader = (loader: "fabric" | "quilt") =>
+	loader === "fabric" ? "Fabric" : "Quilt";
+
+/** List the loader versions of fabric or quilt, newest first. The lists are cached so this works while offline. */
+export async function listLoaderVersions(loader: "fabric" | "quilt") {
+	return invoke<LoaderMetaVersion[]>("plugin:rmcl-game|list_loader_versions", {
+		loader: metaLoader(loader),
+	});
+}
+
+/** List the minecraft versions that fabric or quilt can be installed for, newest first. */
+export async function listLoaderGameVersions(loader: "fabric" | "quilt") {
+	return invoke<LoaderMetaVersion[]>(
+		"plugin:rmcl-game|list_loader_game_versions",
+		{ loader: metaLoader(loader) },
+	);
+}
+
+/** Find the java installations that are already on the system. Nothing is downloaded. */
+export async function findJava() {
+	return invoke<JavaInstall[]>("plugin:rmcl-game|find_java");
+}
+
+export type InstalledRuntime = {
+	/** name of the runtime directory */
+	name: string;
+	path: string;
+	/** major version of the java setting that uses the runtime */
+	major_version: number | null;
+	build: string | null;
+	/** ids of the profiles that need the runtime */
+	profiles: string[];
+};
+
+/** List the java runtimes installed by the launcher and the profiles that need them. */
+export async function listJavaRuntimes() {
+	return invoke<InstalledRuntime[]>("plugin:rmcl-game|list_java_runtimes");
+}
+
+/** Get the newer build of a java runtime, null if it is up to date. */
+export async function checkJavaRuntimeUpdate(name: string) {
+	return invoke<string | null>("plugin:rmcl-game|check_java_runtime_update", {
+		name,
+	});
+}
+
+export async function updateJavaRuntime(name: string) {
+	return invoke<void>("plugin:rmcl-game|update_java_runtime", { name });
+}
+
+export async function uninstallJavaRuntime(name: string) {
+	return invoke<void>("plugin:rmcl-game|uninstall_java_runtime", { name });
+}
+
+/** Remove the java runtimes that no profile needs. Returns the names of the removed runtimes. */
+export async function removeUnusedJavaRuntimes() {
+	return invoke<string[]>("plugin:rmcl-game|remove_unused_java_runtimes");
+}
+
 export async function isRunning(id: string): Promise<boolean> {
 	return invoke<boolean>("plugin:rmcl-game|is_running", {
 		id,
@@ -38,3 +183,18 @@ export async function listActiveProcesses(): Promise<{
 		"plugin:rmcl-game|list_active_processes",
 	);
 }
+
+export type OutputLine = {
+	stream: "stdout" | "stderr";
+	line: string;
+};
+
+export async function streamProcessOutput(
+	profileId: string,
+	channel: Channel<OutputLine>,
+) {
+	return invoke<void>("plugin:rmcl-game|stream_process_output", {
+		profileId,
+		onEvent: channel,
+	});
+}
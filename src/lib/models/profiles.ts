@@ -21,6 +21,10 @@ const profileState = z.enum([
 
 export type ProfileState = z.infer<typeof profileState>;
 
+const gcPreset = z.enum(["default", "g1", "zgc"]);
+
+export type GcPreset = z.infer<typeof gcPreset>;
+
 export class Profile {
 	static schema = z.object({
 		id: z.string().uuid(),
@@ -33,6 +37,9 @@ export class Profile {
 		is_modpack: z.ostring().nullable().default(null),
 		loader_version: z.ostring().nullable().default(null),
 		java_args: z.ostring().nullable().default(null),
+		min_memory: z.number().optional().nullable().default(null),
+		max_memory: z.number().optional().nullable().default(null),
+		gc_preset: gcPreset.optional().nullable().default(null),
 		resolution_width: z.ostring().nullable().default(null),
 		resolution_height: z.ostring().nullable().default(null),
 		state: profileState.default("UNINSTALLED"),
@@ -51,6 +58,9 @@ export class Profile {
 	public loader_version: string | null;
 	public icon: string | null;
 	public java_args: string | null;
+	public min_memory: number | null;
+	public max_memory: number | null;
+	public gc_preset: GcPreset | null;
 	public resolution_width: string | null;
 	public resolution_height: string | null;
 	public is_modpack: string | null;
@@ -65,6 +75,9 @@ export class Profile {
 		this.loader_version = args.loader_version;
 		this.icon = args.icon;
 		this.java_args = args.java_args;
+		this.min_memory = args.min_memory;
+		this.max_memory = args.max_memory;
+		this.gc_preset = args.gc_preset;
 		this.resolution_width = args.resolution_width;
 		this.resolution_height = args.resolution_height;
 		this.state = args.state;
@@ -38,8 +38,12 @@ class DownloadManager extends EventTarget {
 
 	private channel = new Channel<DownloadEvent>();
 	private current: DownloadCurrentItem | null = null;
-	private progress: { amount: number; max: number; status: string } | null =
-		null;
+	private progress: {
+		amount: number;
+		max: number;
+		status: string;
+		transfer?: Extract<DownloadEvent, { event: "transfer" }>["data"];
+	} | null = null;
 
 	private constructor() {
 		super();
@@ -79,6 +83,12 @@ class DownloadManager extends EventTarget {
 				this.dispatchEvent(new Event(DOWNLOAD_MANAGER_EVENT_PROGRESS));
 				break;
 			}
+			case "transfer": {
+				if (this.progress === null) break;
+				this.progress = { ...this.progress, transfer: ev.data };
+				this.dispatchEvent(new Event(DOWNLOAD_MANAGER_EVENT_PROGRESS));
+				break;
+			}
 			case "finished": {
 				await new Promise((ok) => setTimeout(ok, 3000));
 
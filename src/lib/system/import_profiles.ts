@@ -109,8 +109,10 @@ const import_profiles = async () => {
 					: `${loader}${loader !== "vanilla" ? `(${loader_version})` : ""} ${version}`,
 				icon: profile.icon.length ? profile.icon : null,
 				id,
-				java_args:
-					"-Xmx4G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M",
+				java_args: null,
+				min_memory: null,
+				max_memory: null,
+				gc_preset: "g1",
 				loader,
 				last_played: profile.lastUsed ?? new Date().toISOString(),
 				state: "UNINSTALLED",
@@ -124,7 +126,7 @@ const import_profiles = async () => {
 		}
 
 		await transaction((tx) => {
-			tx`INSERT INTO profiles ('id','name','icon','date_created','last_played','version','loader','loader_version','java_args','resolution_width','resolution_height','state') VALUES ${bulk(
+			tx`INSERT INTO profiles ('id','name','icon','date_created','last_played','version','loader','loader_version','java_args','gc_preset','resolution_width','resolution_height','state') VALUES ${bulk(
 				profiles.map((e) => [
 					e.id,
 					e.name,
@@ -135,6 +137,7 @@ const import_profiles = async () => {
 					e.loader,
 					e.loader_version,
 					e.java_args,
+					e.gc_preset,
 					e.resolution_width,
 					e.resolution_width,
 					e.state,
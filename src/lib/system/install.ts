@@ -188,6 +188,8 @@ export async function install_known(
 	const files = [
 		{
 			sha1: file?.hashes.sha1,
+			sha512: file?.hashes.sha512,
+			size: file?.size,
 			url: decodeURIComponent(file?.url),
 			filename: decodeURIComponent(file.filename),
 			version: version.version_number,
@@ -220,6 +222,8 @@ export async function install_known(
 
 						files.push({
 							sha1: dep.file?.hashes.sha1,
+							sha512: dep.file?.hashes.sha512,
+							size: dep.file?.size,
 							url: decodeURIComponent(dep.file?.url),
 							id: dep.id,
 							filename: decodeURIComponent(dep.file?.filename),
@@ -302,6 +306,8 @@ export async function install(data: Project) {
 				const files = [
 					{
 						sha1: file?.hashes.sha1,
+						sha512: file?.hashes.sha512,
+						size: file?.size,
 						url: decodeURIComponent(file?.url),
 						filename: file.filename,
 						version: version.version_number,
@@ -380,6 +386,8 @@ export async function install(data: Project) {
 				const files = [
 					{
 						sha1: file?.hashes.sha1,
+						sha512: file?.hashes.sha512,
+						size: file?.size,
 						url: decodeURIComponent(file?.url),
 						filename: file.filename,
 						version: version.version_number,
@@ -409,6 +417,8 @@ export async function install(data: Project) {
 
 								files.push({
 									sha1: dep.file?.hashes.sha1,
+									sha512: dep.file?.hashes.sha512,
+									size: dep.file?.size,
 									url: decodeURIComponent(dep.file?.url),
 									id: dep.id,
 									filename: dep.file?.filename,
@@ -519,6 +529,8 @@ export async function install(data: Project) {
 						files: [
 							{
 								sha1: file?.hashes.sha1,
+								sha512: file?.hashes.sha512,
+								size: file?.size,
 								url: file?.url,
 								id: data.id,
 								filename: file?.filename,
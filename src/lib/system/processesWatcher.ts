@@ -15,10 +15,25 @@ export const CRASH_EVENT = "crash";
 type ProcessStateEvent =
 	| { type: "Add"; data: string }
 	| { type: "Remove"; data: string[] };
+export type CrashCause =
+	| "missing_mod_dependency"
+	| "wrong_java_version"
+	| "out_of_memory"
+	| "mixin_failure"
+	| "graphics_driver"
+	| "unknown";
+
+export type CrashReport = {
+	cause: CrashCause;
+	summary: string | null;
+	path: string | null;
+};
+
 export type ProcessCrashEvent = {
 	profile: string;
 	code: number;
 	details: string;
+	report: CrashReport | null;
 };
 
 export class ProcessState extends EventTarget {
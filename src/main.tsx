@@ -15,9 +15,11 @@ import { ProcessProvider } from "@component/providers/ProcessProvider";
 import { ModrinthClientApplication } from "./lib/auth/modrinth";
 import { checkForAppUpdate } from "./lib/system/updateCheck";
 import { queryClient } from "@lib/api/queryClient";
+import { KEY_DOWNLOAD_QUEUE } from "@/hooks/keys";
 import { logCatchError } from "./lib/catchError";
 import { initThemes } from "./lib/api/themes";
-import { toastError } from "./lib/toast";
+import { toastError, toastSuccess } from "./lib/toast";
+import { type InstallFailed, retryInstall } from "@lib/api/plugins/content";
 import { getPCA } from "@auth/msal";
 import { router } from "./router";
 import "./index.css";
@@ -40,12 +42,40 @@ Promise.all([getTauriVersion(), getVersion()]).then(([tauri, app]) => {
 		.then(() => info("Shake is ready"));
 });
 
-listen<string>("rmcl-content-install-failed", (ev) => {
-	const payload = ev.payload;
+listen<InstallFailed>("rmcl-content-install-failed", (ev) => {
+	const { id, message, downloads } = ev.payload;
 	toastError({
 		title: "Install failed",
-		description: "A item in the install queue failed",
-		error: payload,
+		description:
+			downloads.length > 0
+				? `${downloads.length} files of a item in the install queue failed to download`
+				: "A item in the install queue failed",
+		error: message,
+		action:
+			downloads.length > 0
+				? {
+					label: "Retry",
+					onClick: () => {
+						retryInstall(id)
+							.then(() => {
+								queryClient.invalidateQueries({
+									queryKey: [KEY_DOWNLOAD_QUEUE],
+								});
+								toastSuccess({
+									title: "Install queued",
+									description: "The item will be installed again",
+								});
+							})
+							.catch((e) =>
+								toastError({
+									title: "Retry failed",
+									description: "The item could not be queued again",
+									error: e as string,
+								}),
+							);
+					},
+				}
+				: undefined,
 	});
 }).catch((e) => console.error(e));
 
@@ -234,22 +234,7 @@ function ProfileEdit() {
 							)}
 						/>
 
-						<FormField
-							control={form.control}
-							name="java_args"
-							render={({ field }) => (
-								<FormItem>
-									<FormLabel>Java Args</FormLabel>
-									<FormControl>
-										<JVMArgForm controller={field} />
-									</FormControl>
-									<FormDescription>
-										Cmd arguments to pass to java on startup.
-									</FormDescription>
-									<FormMessage />
-								</FormItem>
-							)}
-						/>
+						<JVMArgForm control={form.control} profileId={params.id} />
 					</div>
 				</section>
 
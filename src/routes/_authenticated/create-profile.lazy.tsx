@@ -44,8 +44,10 @@ function CreateProfile() {
 			name: "New Profile",
 			version: "latest-release",
 			loader: "vanilla",
-			java_args:
-				"-Xmx4G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC -XX:G1NewSizePercent=20 -XX:G1ReservePercent=20 -XX:MaxGCPauseMillis=50 -XX:G1HeapRegionSize=32M",
+			java_args: null,
+			min_memory: null,
+			max_memory: null,
+			gc_preset: "g1",
 			date_created: new Date().toISOString(),
 		},
 	});
@@ -197,24 +199,9 @@ function CreateProfile() {
 
 						<CopyProfileOptions form={form} />
 
-						<FormField
-							control={form.control}
-							name="java_args"
-							render={({ field }) => (
-								<FormItem>
-									<FormLabel>Java Args</FormLabel>
-									<FormControl>
-										<Suspense>
-											<JVMArgForm controller={field} />
-										</Suspense>
-									</FormControl>
-									<FormDescription>
-										Command line arguments to pass to java on startup.
-									</FormDescription>
-									<FormMessage />
-								</FormItem>
-							)}
-						/>
+						<Suspense>
+							<JVMArgForm control={form.control} />
+						</Suspense>
 					</section>
 
 					<div className="absolute bottom-4 right-4">
@@ -10,12 +10,28 @@ import { ScrollArea } from "@component/ui/scroll-area";
 import { QueueItemState } from "@/lib/QueueItemState";
 import { Loading } from "@/components/Loading";
 import { useDownloadProgress } from "@/hooks/useDownloadProgress";
+import type { DownloadEvent } from "@/lib/api/plugins/content";
+import { formatSize } from "@/lib/size_format";
 
 export const Route = createLazyFileRoute("/_authenticated/downloads")({
 	component: Download,
 	pendingComponent: Loading,
 });
 
+const size = (bytes: number) =>
+	bytes > 0 ? formatSize(bytes, { digit: 1, space: true }) : "0 byte";
+
+function formatTransfer(
+	transfer: Extract<DownloadEvent, { event: "transfer" }>["data"],
+) {
+	const eta =
+		transfer.eta_seconds === null
+			? ""
+			: ` - ${Math.floor(transfer.eta_seconds / 60)}m ${transfer.eta_seconds % 60}s left`;
+
+	return `${size(transfer.bytes_done)} of ${size(transfer.bytes_total)} (${transfer.files_done}/${transfer.files_total} files) at ${size(transfer.bytes_per_second)}/s${eta}`;
+}
+
 function Download() {
 	const current = useCurrentQueue();
 	const progress = useDownloadProgress();
@@ -45,6 +61,11 @@ function Download() {
 							<div>
 								{progress.amount} of {progress.max}
 							</div>
+							{progress.transfer ? (
+								<TypographyMuted>
+									{formatTransfer(progress.transfer)}
+								</TypographyMuted>
+							) : null}
 						</div>
 					</div>
 				) : null}
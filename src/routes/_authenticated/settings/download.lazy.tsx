@@ -16,18 +16,56 @@ import {
 	FormLabel,
 	FormMessage,
 } from "@/components/ui/form";
-import { getConfig, updateConfig } from "@/lib/models/settings";
+import {
+	getConfig,
+	isOption,
+	updateConfig,
+	upsert,
+} from "@/lib/models/settings";
+import {
+	checkJavaRuntimeUpdate,
+	findJava,
+	listJavaRuntimes,
+	removeUnusedJavaRuntimes,
+	uninstallJavaRuntime,
+	updateJavaRuntime,
+	type InstalledRuntime,
+	type JavaInstall,
+} from "@/lib/api/plugins/game";
 import { checkForAppUpdate } from "@/lib/system/updateCheck";
+import { toastError, toastSuccess } from "@/lib/toast";
+import { reloadNetworkSettings } from "@/lib/api/plugins/content";
 import { Separator } from "@/components/ui/separator";
 import { queryClient } from "@/lib/api/queryClient";
 import { Button } from "@/components/ui/button";
 import { Loading } from "@/components/Loading";
 import { Input } from "@/components/ui/input";
+import { Switch } from "@/components/ui/switch";
+import {
+	Select,
+	SelectContent,
+	SelectItem,
+	SelectTrigger,
+	SelectValue,
+} from "@/components/ui/select";
 
 const JavaJREForamt =
 	/zulu(?<zulu>\d+\.\d+\.\d+)-ca-jre(?<jre>\d+\.\d+\.\d+)-(?<platform>\w+)/;
 
 const OPTION_PATH_APP = "path.app";
+const OPTION_JRE_PROVIDER = "option.jre_provider";
+const OPTION_DOWNLOAD_CONNECTIONS = "option.download_connections";
+const OPTION_DOWNLOAD_HOST_CONNECTIONS = "option.download_host_connections";
+const OPTION_DOWNLOAD_BANDWIDTH = "option.download_bandwidth";
+const OPTION_MIRROR = "option.mirror";
+const OPTION_OFFLINE_MODE = "option.offline_mode";
+const OPTION_HTTP_PROXY = "option.http_proxy";
+const OPTION_HTTP_PROXY_USERNAME = "option.http_proxy_username";
+const OPTION_HTTP_PROXY_PASSWORD = "option.http_proxy_password";
+const OPTION_HTTP_CA_BUNDLE = "option.http_ca_bundle";
+const OPTION_HTTP_CONNECT_TIMEOUT = "option.http_connect_timeout";
+const OPTION_HTTP_READ_TIMEOUT = "option.http_read_timeout";
+const OPTION_HTTP_PREFER_IPV4 = "option.http_prefer_ipv4";
 const APLICATION_RUNTIMES_AND_VERSIONS = "APPLICATION_RUNTIMES_AND_VERSIONS";
 
 export const Route = createLazyFileRoute("/_authenticated/settings/download")({
@@ -64,6 +102,76 @@ const UpdateCheckBtn: React.FC = () => {
 	);
 };
 
+const selectJava = async (install: JavaInstall) => {
+	const key = `java.${install.major_version}`;
+	await upsert(key, install.path);
+	await updateConfig(key, install.path, install.version);
+	toastSuccess({ title: `Using ${install.path} for Java ${install.major_version}` });
+};
+
+const FindJava: React.FC = () => {
+	const [isSearching, setIsSearching] = useState(false);
+	const [installs, setInstalls] = useState<JavaInstall[]>();
+
+	return (
+		<div className="space-y-2">
+			<Button
+				disabled={isSearching}
+				className="w-full"
+				variant="outline"
+				onClick={() => {
+					setIsSearching(true);
+					findJava()
+						.then(setInstalls)
+						.catch((e) => {
+							if (e instanceof Error) error(e.message);
+							console.error(e);
+						})
+						.finally(() => setIsSearching(false));
+				}}
+			>
+				{isSearching ? (
+					<span className="inline-flex gap-2 items-center">
+						Searching for java <Loader2 className="animate-spin" />
+					</span>
+				) : (
+					<span>Find installed java</span>
+				)}
+			</Button>
+			{installs?.length === 0 ? (
+				<p className="text-muted-foreground text-sm">No java installations were found.</p>
+			) : null}
+			<ul className="w-full max-h-48 overflow-y-scroll divide-y">
+				{installs?.map((install) => (
+					<li
+						className="flex items-center justify-between gap-2 p-2 hover:bg-gray-300/10"
+						key={install.path}
+					>
+						<div className="overflow-hidden">
+							<p>
+								Java {install.version} ({install.vendor ?? "Unknown"},{" "}
+								{install.arch ?? "Unknown"}
+								{install.data_model ? `, ${install.data_model}-bit` : ""})
+							</p>
+							<p className="text-muted-foreground text-xs truncate">
+								{install.path}
+							</p>
+						</div>
+						<Button
+							size="sm"
+							variant="secondary"
+							type="button"
+							onClick={() => selectJava(install)}
+						>
+							Use for Java {install.major_version}
+						</Button>
+					</li>
+				))}
+			</ul>
+		</div>
+	);
+};
+
 const getVersions = async (runtimeDir: string) => {
 	const dir = await join(runtimeDir, "versions");
 	const doesExist = await exists(dir);
@@ -71,34 +179,128 @@ const getVersions = async (runtimeDir: string) => {
 	return readDir(dir);
 };
 
-const getInstalledJREs = async (runtimeDir: string) => {
-	const dir = await join(runtimeDir, "java");
-	const doesExist = await exists(dir);
-	if (!doesExist) return [];
+const runtimeName = (runtime: InstalledRuntime) => {
+	const match = runtime.name.match(JavaJREForamt);
+	if (!match) {
+		return runtime.major_version
+			? `Java ${runtime.major_version} (${runtime.name})`
+			: runtime.name;
+	}
 
-	const folders = await readDir(dir);
+	const zuluBuild = match.groups?.zulu ?? "Unknown";
+	const jre = match.groups?.jre ?? "Unknown";
+	const platform = match.groups?.platform ?? "Unknown";
 
-	const versions = [];
-	for (const folder of folders) {
-		const match = folder.name.match(JavaJREForamt);
-		if (!match) {
-			versions.push({ name: folder.name, folder: folder.name });
-			continue;
-		}
+	return `Java ${jre} for ${platform}. (Zulu ${zuluBuild})`;
+};
 
-		const zuluBuild = match.groups?.zulu ?? "Unknown";
-		const jre = match.groups?.jre ?? "Unknown";
-		const platform = match.groups?.platform ?? "Unknown";
+const reportError = (e: unknown) => {
+	if (e instanceof Error) error(e.message);
+	console.error(e);
+};
 
-		versions.push({
-			name: `Java ${jre} for ${platform}. (Zulu ${zuluBuild})`,
-			folder: folder.name,
-		});
-	}
+const RuntimeItem: React.FC<{ runtime: InstalledRuntime }> = ({ runtime }) => {
+	const [isBusy, setIsBusy] = useState(false);
+	const [update, setUpdate] = useState<string | null>();
 
-	return versions;
+	const run = (action: () => Promise<void>) => {
+		setIsBusy(true);
+		action()
+			.catch(reportError)
+			.finally(() => setIsBusy(false));
+	};
+
+	return (
+		<li className="flex items-center justify-between gap-2 p-2 hover:bg-gray-300/10">
+			<div className="overflow-hidden">
+				<p>{runtimeName(runtime)}</p>
+				<p className="text-muted-foreground text-xs">
+					{runtime.profiles.length
+						? `Used by ${runtime.profiles.length} profile(s)`
+						: "Not used by any profile"}
+				</p>
+			</div>
+			<div className="flex gap-2">
+				{update === undefined ? (
+					<Button
+						size="sm"
+						variant="outline"
+						type="button"
+						disabled={isBusy || runtime.major_version === null}
+						onClick={() =>
+							run(() => checkJavaRuntimeUpdate(runtime.name).then(setUpdate))
+						}
+					>
+						Check for update
+					</Button>
+				) : update ? (
+					<Button
+						size="sm"
+						variant="secondary"
+						type="button"
+						disabled={isBusy}
+						onClick={() =>
+							run(async () => {
+								await updateJavaRuntime(runtime.name);
+								toastSuccess({ title: `Updated to Java ${update}` });
+								await queryClient.invalidateQueries({
+									queryKey: [APLICATION_RUNTIMES_AND_VERSIONS],
+								});
+							})
+						}
+					>
+						Update to {update}
+					</Button>
+				) : (
+					<span className="text-muted-foreground text-sm">Up to date</span>
+				)}
+				<Button
+					size="sm"
+					variant="destructive"
+					type="button"
+					disabled={isBusy || runtime.profiles.length > 0}
+					onClick={() =>
+						run(async () => {
+							await uninstallJavaRuntime(runtime.name);
+							await queryClient.invalidateQueries({
+								queryKey: [APLICATION_RUNTIMES_AND_VERSIONS],
+							});
+						})
+					}
+				>
+					Remove
+				</Button>
+			</div>
+		</li>
+	);
 };
 
+type DownloadSettingsForm = {
+	dir: string;
+	jreProvider: string;
+	connections: string;
+	hostConnections: string;
+	bandwidth: string;
+	mirror: string;
+	offlineMode: boolean;
+	proxy: string;
+	proxyUsername: string;
+	proxyPassword: string;
+	caBundle: string;
+	connectTimeout: string;
+	readTimeout: string;
+	preferIpv4: boolean;
+};
+
+const textOption = (key: string) =>
+	getConfig(key).then((e) => e?.value ?? "");
+
+const limitRule = (min: number) => ({
+	required: { message: "A value is required", value: true },
+	pattern: { message: "Must be a whole number", value: /^\d+$/ },
+	min: { message: `Must be at least ${min}`, value: min },
+});
+
 function DownloadSettings() {
 	const { data } = useSuspenseQuery({
 		queryKey: [APLICATION_RUNTIMES_AND_VERSIONS],
@@ -108,7 +310,7 @@ function DownloadSettings() {
 			const runtime_dir = await join(path.value, "runtime");
 			const [versions, java] = await Promise.all([
 				getVersions(runtime_dir),
-				getInstalledJREs(runtime_dir),
+				listJavaRuntimes(),
 			]);
 
 			return {
@@ -117,16 +319,90 @@ function DownloadSettings() {
 			};
 		},
 	});
-	const form = useForm<{ dir: string }>({
+	const form = useForm<DownloadSettingsForm>({
 		async defaultValues() {
-			const paths = await getConfig(OPTION_PATH_APP);
+			const [
+				paths,
+				jreProvider,
+				connections,
+				hostConnections,
+				bandwidth,
+				mirror,
+				offlineMode,
+			] = await Promise.all([
+				getConfig(OPTION_PATH_APP),
+				getConfig(OPTION_JRE_PROVIDER).then((e) => e?.value ?? "zulu"),
+				getConfig(OPTION_DOWNLOAD_CONNECTIONS).then((e) => e?.value ?? "24"),
+				getConfig(OPTION_DOWNLOAD_HOST_CONNECTIONS).then(
+					(e) => e?.value ?? "8",
+				),
+				getConfig(OPTION_DOWNLOAD_BANDWIDTH).then((e) => e?.value ?? "0"),
+				getConfig(OPTION_MIRROR).then((e) => e?.value ?? ""),
+				isOption(OPTION_OFFLINE_MODE, "TRUE"),
+			]);
+			const [
+				proxy,
+				proxyUsername,
+				proxyPassword,
+				caBundle,
+				connectTimeout,
+				readTimeout,
+				preferIpv4,
+			] = await Promise.all([
+				textOption(OPTION_HTTP_PROXY),
+				textOption(OPTION_HTTP_PROXY_USERNAME),
+				textOption(OPTION_HTTP_PROXY_PASSWORD),
+				textOption(OPTION_HTTP_CA_BUNDLE),
+				getConfig(OPTION_HTTP_CONNECT_TIMEOUT).then((e) => e?.value ?? "0"),
+				getConfig(OPTION_HTTP_READ_TIMEOUT).then((e) => e?.value ?? "0"),
+				isOption(OPTION_HTTP_PREFER_IPV4, "TRUE"),
+			]);
 			const path = paths?.value;
 			if (!path) throw new Error("Failed to get dir");
-			return { dir: path };
+			return {
+				dir: path,
+				jreProvider,
+				connections,
+				hostConnections,
+				bandwidth,
+				mirror,
+				offlineMode,
+				proxy,
+				proxyUsername,
+				proxyPassword,
+				caBundle,
+				connectTimeout,
+				readTimeout,
+				preferIpv4,
+			};
 		},
 	});
 
-	const onSubmit = async (state: { dir: string }) => {
+	const onSubmit = async (state: DownloadSettingsForm) => {
+		await Promise.all([
+			upsert(OPTION_JRE_PROVIDER, state.jreProvider),
+			upsert(OPTION_DOWNLOAD_CONNECTIONS, state.connections),
+			upsert(OPTION_DOWNLOAD_HOST_CONNECTIONS, state.hostConnections),
+			upsert(OPTION_DOWNLOAD_BANDWIDTH, state.bandwidth),
+			upsert(OPTION_MIRROR, state.mirror.trim()),
+			upsert(OPTION_OFFLINE_MODE, state.offlineMode ? "TRUE" : "FALSE"),
+			upsert(OPTION_HTTP_PROXY, state.proxy.trim()),
+			upsert(OPTION_HTTP_PROXY_USERNAME, state.proxyUsername.trim()),
+			upsert(OPTION_HTTP_PROXY_PASSWORD, state.proxyPassword),
+			upsert(OPTION_HTTP_CA_BUNDLE, state.caBundle.trim()),
+			upsert(OPTION_HTTP_CONNECT_TIMEOUT, state.connectTimeout),
+			upsert(OPTION_HTTP_READ_TIMEOUT, state.readTimeout),
+			upsert(OPTION_HTTP_PREFER_IPV4, state.preferIpv4 ? "TRUE" : "FALSE"),
+		]);
+
+		await reloadNetworkSettings().catch((e) =>
+			toastError({
+				title: "Network settings",
+				description: "Failed to apply the network settings",
+				error: e as string,
+			}),
+		);
+
 		if (!(await exists(state.dir))) {
 			form.setError("dir", {
 				type: "validate",
@@ -182,6 +458,267 @@ function DownloadSettings() {
 							)}
 						/>
 
+						<FormField
+							control={form.control}
+							name="jreProvider"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>Java Provider</FormLabel>
+									<FormControl>
+										<Select value={field.value} onValueChange={field.onChange}>
+											<SelectTrigger className="w-full">
+												<SelectValue />
+											</SelectTrigger>
+											<SelectContent>
+												<SelectItem value="zulu">Azul Zulu</SelectItem>
+												<SelectItem value="mojang">Mojang</SelectItem>
+											</SelectContent>
+										</Select>
+									</FormControl>
+									<FormDescription>
+										Where java runtimes are downloaded from when a version needs
+										one. Mojang installs the same runtime as the official
+										launcher.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<FormField
+							rules={limitRule(1)}
+							control={form.control}
+							name="connections"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>Max Connections</FormLabel>
+									<FormControl>
+										<Input {...field} type="number" min={1} />
+									</FormControl>
+									<FormDescription>
+										Max number of files that are downloaded at the same time.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<FormField
+							rules={limitRule(1)}
+							control={form.control}
+							name="hostConnections"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>Max Connections per Server</FormLabel>
+									<FormControl>
+										<Input {...field} type="number" min={1} />
+									</FormControl>
+									<FormDescription>
+										Max number of files that are downloaded from one server at
+										the same time.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<FormField
+							rules={limitRule(0)}
+							control={form.control}
+							name="bandwidth"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>Bandwidth Limit (KB/s)</FormLabel>
+									<FormControl>
+										<Input {...field} type="number" min={0} />
+									</FormControl>
+									<FormDescription>
+										Max download speed of the launcher, 0 for no limit. Changes
+										apply to the next download.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<FormField
+							rules={{
+								pattern: {
+									message: "Must be empty, bmclapi or a http(s) url",
+									value: /^\s*(bmclapi|https?:\/\/\S+)?\s*$/,
+								},
+							}}
+							control={form.control}
+							name="mirror"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>Download Mirror</FormLabel>
+									<FormControl>
+										<Input {...field} placeholder="bmclapi" />
+									</FormControl>
+									<FormDescription>
+										Download game files from a mirror instead of the official
+										servers. Use bmclapi or the url of a mirror with the same
+										layout, leave empty for the official servers.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<FormField
+							control={form.control}
+							name="offlineMode"
+							render={({ field }) => (
+								<FormItem>
+									<div className="flex flex-row-reverse items-center justify-end gap-2">
+										<FormLabel>Offline mode</FormLabel>
+										<FormControl>
+											<Switch
+												checked={field.value}
+												onCheckedChange={(ev) => field.onChange(ev)}
+											/>
+										</FormControl>
+									</div>
+									<FormDescription>
+										Launch installed profiles without contacting any servers.
+										Downloads are postponed until offline mode is turned off.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<FormField
+							rules={{
+								pattern: {
+									message: "Must be empty or a http(s) url",
+									value: /^\s*(https?:\/\/\S+)?\s*$/,
+								},
+							}}
+							control={form.control}
+							name="proxy"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>Proxy</FormLabel>
+									<FormControl>
+										<Input {...field} placeholder="http://proxy.example.com:3128" />
+									</FormControl>
+									<FormDescription>
+										Send all game downloads through this proxy, leave empty to
+										use the system proxy.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<div className="flex gap-4">
+							<FormField
+								control={form.control}
+								name="proxyUsername"
+								render={({ field }) => (
+									<FormItem className="flex-1">
+										<FormLabel>Proxy Username</FormLabel>
+										<FormControl>
+											<Input {...field} autoComplete="off" />
+										</FormControl>
+										<FormMessage />
+									</FormItem>
+								)}
+							/>
+							<FormField
+								control={form.control}
+								name="proxyPassword"
+								render={({ field }) => (
+									<FormItem className="flex-1">
+										<FormLabel>Proxy Password</FormLabel>
+										<FormControl>
+											<Input {...field} type="password" autoComplete="off" />
+										</FormControl>
+										<FormDescription>
+											Saved unencrypted in the launcher settings.
+										</FormDescription>
+										<FormMessage />
+									</FormItem>
+								)}
+							/>
+						</div>
+
+						<FormField
+							control={form.control}
+							name="caBundle"
+							render={({ field }) => (
+								<FormItem>
+									<FormLabel>CA Certificates</FormLabel>
+									<FormControl>
+										<Input {...field} placeholder="path to a .pem file" />
+									</FormControl>
+									<FormDescription>
+										Extra root certificates to trust, for networks that inspect
+										https traffic.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
+						<div className="flex gap-4">
+							<FormField
+								rules={limitRule(0)}
+								control={form.control}
+								name="connectTimeout"
+								render={({ field }) => (
+									<FormItem className="flex-1">
+										<FormLabel>Connect Timeout</FormLabel>
+										<FormControl>
+											<Input {...field} type="number" min={0} />
+										</FormControl>
+										<FormDescription>Seconds, 0 for no limit.</FormDescription>
+										<FormMessage />
+									</FormItem>
+								)}
+							/>
+							<FormField
+								rules={limitRule(0)}
+								control={form.control}
+								name="readTimeout"
+								render={({ field }) => (
+									<FormItem className="flex-1">
+										<FormLabel>Read Timeout</FormLabel>
+										<FormControl>
+											<Input {...field} type="number" min={0} />
+										</FormControl>
+										<FormDescription>Seconds, 0 for no limit.</FormDescription>
+										<FormMessage />
+									</FormItem>
+								)}
+							/>
+						</div>
+
+						<FormField
+							control={form.control}
+							name="preferIpv4"
+							render={({ field }) => (
+								<FormItem>
+									<div className="flex flex-row-reverse items-center justify-end gap-2">
+										<FormLabel>Prefer IPv4</FormLabel>
+										<FormControl>
+											<Switch
+												checked={field.value}
+												onCheckedChange={(ev) => field.onChange(ev)}
+											/>
+										</FormControl>
+									</div>
+									<FormDescription>
+										Try IPv4 addresses before IPv6 ones, for networks with broken
+										IPv6.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
 						<div className="flex w-full justify-end">
 							<Button type="submit">Save</Button>
 						</div>
@@ -213,18 +750,31 @@ function DownloadSettings() {
 				<p className="mt-0 text-muted-foreground text-sm">
 					All installed java versions.
 				</p>
-				<ul className="w-full max-h-32 overflow-y-scroll divide-y">
-					{data.java.map((version, key) => (
-						<li
-							className="hover:bg-gray-300/10"
-							key={`${version.name}_${key + 1}`}
-						>
-							<button type="button" className="p-2 w-full text-left">
-								{version.name}
-							</button>
-						</li>
+				<ul className="w-full max-h-48 overflow-y-scroll divide-y">
+					{data.java.map((runtime) => (
+						<RuntimeItem key={runtime.name} runtime={runtime} />
 					))}
 				</ul>
+				<Button
+					className="w-full mb-2"
+					variant="outline"
+					type="button"
+					onClick={() =>
+						removeUnusedJavaRuntimes()
+							.then(async (removed) => {
+								toastSuccess({
+									title: `Removed ${removed.length} unused java runtime(s)`,
+								});
+								await queryClient.invalidateQueries({
+									queryKey: [APLICATION_RUNTIMES_AND_VERSIONS],
+								});
+							})
+							.catch(reportError)
+					}
+				>
+					Remove unused runtimes
+				</Button>
+				<FindJava />
 			</section>
 		</div>
 	);
@@ -20,13 +20,22 @@ import { Button } from "@/components/ui/button";
 import { Switch } from "@/components/ui/switch";
 import { Loading } from "@/components/Loading";
 import { Input } from "@/components/ui/input";
+import {
+	Select,
+	SelectContent,
+	SelectItem,
+	SelectTrigger,
+	SelectValue,
+} from "@/components/ui/select";
 import { toastSuccess } from "@/lib/toast";
 
-type State = { exitOnStart: boolean; copyOptionsFrom?: string, maxLaunchWait?: number };
+type State = { exitOnStart: boolean; repairOnLaunch?: boolean; copyOptionsFrom?: string, maxLaunchWait?: number, readyDetection?: string };
 
 const OPTION_EXIT_ON_START = "option.exit_on_start";
 const OPTION_COPY_SETTINGS = "option.copy_settings_from";
 const OPTION_MAX_LAUNCH_WAIT = "option.max_launch_wait";
+const OPTION_READY_DETECTION = "option.ready_detection";
+const OPTION_REPAIR_ON_LAUNCH = "option.repair_on_launch";
 
 const selectFile = async () => {
 	const selected = await open({
@@ -56,17 +65,19 @@ function GameSettings() {
 	const form = useForm<State>({
 		async defaultValues() {
 			try {
-				const [exitOnStart, copyOptionsFrom, maxLaunchWait] = await Promise.all([
+				const [exitOnStart, copyOptionsFrom, maxLaunchWait, readyDetection, repairOnLaunch] = await Promise.all([
 					isOption(OPTION_EXIT_ON_START, "TRUE"),
 					getConfig(OPTION_COPY_SETTINGS).then((e) => e?.value),
 					getConfig(OPTION_MAX_LAUNCH_WAIT).then(e => {
 						if (!e?.value) return;
 						const ticks = Number.parseInt(e.value);
 						return millisecondsToMinutes(ticks * 250);
-					})
+					}),
+					getConfig(OPTION_READY_DETECTION).then((e) => e?.value ?? "auto"),
+					isOption(OPTION_REPAIR_ON_LAUNCH, "TRUE"),
 				]);
 
-				return { exitOnStart, copyOptionsFrom, maxLaunchWait };
+				return { exitOnStart, copyOptionsFrom, maxLaunchWait, readyDetection, repairOnLaunch };
 			} catch (error) {
 				return { exitOnStart: false };
 			}
@@ -75,6 +86,7 @@ function GameSettings() {
 
 	const onSubmit = async (state: State) => {
 		await upsert(OPTION_EXIT_ON_START, state.exitOnStart ? "TRUE" : "FALSE");
+		await upsert(OPTION_REPAIR_ON_LAUNCH, state.repairOnLaunch ? "TRUE" : "FALSE");
 
 		if (state.copyOptionsFrom) {
 			await upsert(OPTION_COPY_SETTINGS, state.copyOptionsFrom);
@@ -85,6 +97,10 @@ function GameSettings() {
 			await upsert(OPTION_MAX_LAUNCH_WAIT, ticks);
 		}
 
+		if (state.readyDetection) {
+			await upsert(OPTION_READY_DETECTION, state.readyDetection);
+		}
+
 		toastSuccess({ title: "Settings Saved" });
 	};
 
@@ -164,6 +180,28 @@ function GameSettings() {
 							)}
 						/>
 
+						<FormField
+							name="repairOnLaunch"
+							control={form.control}
+							render={({ field }) => (
+								<FormItem>
+									<div className="flex flex-row-reverse items-center justify-end gap-2">
+										<FormLabel>Repair on launch</FormLabel>
+										<FormControl>
+											<Switch
+												checked={field.value}
+												onCheckedChange={(ev) => field.onChange(ev)}
+											/>
+										</FormControl>
+									</div>
+									<FormDescription>
+										Queue a reinstall of the game when files are missing or corrupt on launch.
+									</FormDescription>
+									<FormMessage />
+								</FormItem>
+							)}
+						/>
+
 						<FormField name="maxLaunchWait" control={form.control} rules={{
 							min: { message: "Min wait time is a 1min", value: 1 },
 							max: { message: "Outside the max wait time", value: 30 },
@@ -180,6 +218,30 @@ function GameSettings() {
 							</FormItem>
 						)} />
 
+						<FormField name="readyDetection" control={form.control} render={({ field }) => (
+							<FormItem>
+								<FormLabel>Ready Detection</FormLabel>
+								<FormControl>
+									<Select value={field.value} onValueChange={field.onChange}>
+										<SelectTrigger className="w-full">
+											<SelectValue />
+										</SelectTrigger>
+										<SelectContent>
+											<SelectItem value="auto">Auto</SelectItem>
+											<SelectItem value="log_pattern">Log Message</SelectItem>
+											<SelectItem value="window">Game Window (Windows only)</SelectItem>
+											<SelectItem value="log_activity">Log Activity</SelectItem>
+											<SelectItem value="none">None</SelectItem>
+										</SelectContent>
+									</Select>
+								</FormControl>
+								<FormDescription>
+									How the launcher detects that the game has started. None skips waiting for the game.
+								</FormDescription>
+								<FormMessage />
+							</FormItem>
+						)} />
+
 						<div className="flex w-full justify-end">
 							<Button type="submit">Save</Button>
 						</div>